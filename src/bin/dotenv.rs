@@ -4,11 +4,17 @@ use std::ffi::OsString;
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
-use std::process::{self, Command};
+use std::process::{self, Child, Command, ExitStatus};
+use std::time::{Duration, Instant};
 
-use dotenvor::{EnvLoader, Error, KeyParsingMode, SubstitutionMode, TargetEnv};
+use dotenvor::{
+    Convention, EnvLoader, EnvWatcher, Error, KeyParsingMode, KeyPattern, ProtectedKeys,
+    SearchBounds, SubstitutionMode, TargetEnv,
+};
 
 const DEFAULT_FILE: &str = ".env";
+const DEFAULT_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+const WATCH_INTERVAL: Duration = Duration::from_millis(100);
 
 const HELP: &str = "\
 dotenv - run commands with variables loaded from dotenv files
@@ -16,11 +22,15 @@ dotenv - run commands with variables loaded from dotenv files
 Usage:
   dotenv run [OPTIONS] -- COMMAND [ARGS...]
   dotenv run [OPTIONS] COMMAND [ARGS...]
+  dotenv encrypt [OPTIONS]
+  dotenv decrypt [OPTIONS]
   dotenv --help
   dotenv --version
 
 Commands:
   run       Load dotenv files and execute a command
+  encrypt   Encrypt values in dotenv files in place
+  decrypt   Decrypt values in dotenv files in place
 ";
 
 const RUN_HELP: &str = "\
@@ -32,19 +42,72 @@ Usage:
 
 Options:
   -f, --file <PATHS>      Dotenv file path(s). Repeat or pass comma-separated paths.
-                          Defaults to .env.
+                          Globs such as config/*.env or .env.{shared,local}
+                          load every match. Defaults to .env.
+  -e, --env <NAME>        Load the .env, .env.<NAME>, .env.local, and
+                          .env.<NAME>.local stack before any --file paths.
+                          Missing files in the stack are skipped.
   -i, --ignore            Ignore missing dotenv files.
       --ignore-missing    Alias for --ignore.
   -o, --override          Override existing environment variables.
       --overload          Alias for --override.
   -u, --search-upward     Search parent directories for relative dotenv files.
+      --cascade           Load matching files from every parent directory up
+                          to the project root (.git or Cargo.toml), $HOME,
+                          or a filesystem boundary; deeper files take
+                          precedence.
       --expand            Expand variable placeholders in values.
       --permissive-keys   Accept permissive key syntax.
+      --allow-key <KEYS>  Allow protected keys such as PATH or LD_PRELOAD.
+                          Repeat or pass comma-separated keys.
+  -w, --watch             Keep running and restart the command when the
+                          loaded values change. The command runs in its own
+                          process group and gets the terminal; signals sent
+                          to dotenv are forwarded to it, and dotenv exits
+                          with the command's status.
+      --stop-timeout <SECONDS>
+                          With --watch, how long to wait after a restart or
+                          a forwarded signal before killing the command.
+                          Defaults to 10.
   -v, --verbose           Print loader diagnostics to stderr.
   -q, --quiet             Suppress loader diagnostics.
   -h, --help              Show this help text.
 ";
 
+const ENCRYPT_HELP: &str = "\
+dotenv encrypt - encrypt values in dotenv files in place
+
+Usage:
+  dotenv encrypt [OPTIONS]
+
+Generates a key pair when a file has no DOTENV_PUBLIC_KEY entry and appends
+the private key to .env.keys next to the file.
+
+Options:
+  -f, --file <PATHS>  Dotenv file path(s). Repeat or pass comma-separated paths.
+                      Defaults to .env.
+  -k, --key <KEYS>    Only encrypt these keys. Repeat or pass comma-separated
+                      keys; `*` and `?` globs are allowed.
+  -h, --help          Show this help text.
+";
+
+const DECRYPT_HELP: &str = "\
+dotenv decrypt - decrypt values in dotenv files in place
+
+Usage:
+  dotenv decrypt [OPTIONS]
+
+Reads the private key from DOTENV_PRIVATE_KEY_<ENV>, DOTENV_PRIVATE_KEY, or
+.env.keys next to the file.
+
+Options:
+  -f, --file <PATHS>  Dotenv file path(s). Repeat or pass comma-separated paths.
+                      Defaults to .env.
+  -k, --key <KEYS>    Only decrypt these keys. Repeat or pass comma-separated
+                      keys; `*` and `?` globs are allowed.
+  -h, --help          Show this help text.
+";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum RunCommand {
     Help,
@@ -54,26 +117,48 @@ enum RunCommand {
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct RunOptions {
     files: Vec<PathBuf>,
+    environment: Option<String>,
     required: bool,
     override_existing: bool,
     search_upward: bool,
+    cascade: bool,
     substitution_mode: SubstitutionMode,
     key_parsing_mode: KeyParsingMode,
+    allowed_keys: Vec<String>,
+    watch: bool,
+    stop_timeout: Duration,
     verbose: bool,
     quiet: bool,
     command: OsString,
     args: Vec<OsString>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CryptCommand {
+    Help,
+    Execute(CryptOptions),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct CryptOptions {
+    files: Vec<PathBuf>,
+    keys: Vec<String>,
+}
+
 impl Default for RunOptions {
     fn default() -> Self {
         Self {
             files: Vec::new(),
+            environment: None,
             required: true,
             override_existing: false,
             search_upward: false,
+            cascade: false,
             substitution_mode: SubstitutionMode::Disabled,
             key_parsing_mode: KeyParsingMode::Strict,
+            allowed_keys: Vec::new(),
+            watch: false,
+            stop_timeout: DEFAULT_STOP_TIMEOUT,
             verbose: false,
             quiet: false,
             command: OsString::new(),
@@ -123,6 +208,27 @@ fn run(args: impl IntoIterator<Item = OsString>) -> i32 {
                 1
             }
         },
+        name @ ("encrypt" | "decrypt") => {
+            let encrypt = name == "encrypt";
+            match parse_crypt_options(name, args.collect()) {
+                Ok(CryptCommand::Help) => {
+                    println!("{}", if encrypt { ENCRYPT_HELP } else { DECRYPT_HELP });
+                    0
+                }
+                Ok(CryptCommand::Execute(options)) => match execute_crypt(encrypt, options) {
+                    Ok(()) => 0,
+                    Err(err) => {
+                        eprintln!("dotenv: {err}");
+                        1
+                    }
+                },
+                Err(err) => {
+                    eprintln!("dotenv: {err}");
+                    eprintln!("Try `dotenv {name} --help`.");
+                    1
+                }
+            }
+        }
         unknown => {
             eprintln!("dotenv: unknown subcommand `{unknown}`");
             eprintln!("Try `dotenv --help`.");
@@ -154,6 +260,18 @@ fn parse_run_options(args: Vec<OsString>) -> Result<RunCommand, String> {
                 parse_file_text(&value["--file=".len()..], &mut options.files)?;
                 index += 1;
             }
+            "-e" | "--env" => {
+                index += 1;
+                let Some(value) = args.get(index) else {
+                    return Err("missing value for `-e/--env`".to_owned());
+                };
+                options.environment = Some(parse_environment(&value.to_string_lossy())?);
+                index += 1;
+            }
+            value if value.starts_with("--env=") => {
+                options.environment = Some(parse_environment(&value["--env=".len()..])?);
+                index += 1;
+            }
             "-i" | "--ignore" | "--ignore-missing" => {
                 options.required = false;
                 index += 1;
@@ -166,6 +284,10 @@ fn parse_run_options(args: Vec<OsString>) -> Result<RunCommand, String> {
                 options.search_upward = true;
                 index += 1;
             }
+            "--cascade" => {
+                options.cascade = true;
+                index += 1;
+            }
             "--expand" => {
                 options.substitution_mode = SubstitutionMode::Expand;
                 index += 1;
@@ -174,6 +296,34 @@ fn parse_run_options(args: Vec<OsString>) -> Result<RunCommand, String> {
                 options.key_parsing_mode = KeyParsingMode::Permissive;
                 index += 1;
             }
+            "--allow-key" => {
+                index += 1;
+                let Some(value) = args.get(index) else {
+                    return Err("missing value for `--allow-key`".to_owned());
+                };
+                parse_allowed_keys(&value.to_string_lossy(), &mut options.allowed_keys)?;
+                index += 1;
+            }
+            value if value.starts_with("--allow-key=") => {
+                parse_allowed_keys(&value["--allow-key=".len()..], &mut options.allowed_keys)?;
+                index += 1;
+            }
+            "-w" | "--watch" => {
+                options.watch = true;
+                index += 1;
+            }
+            "--stop-timeout" => {
+                index += 1;
+                let Some(value) = args.get(index) else {
+                    return Err("missing value for `--stop-timeout`".to_owned());
+                };
+                options.stop_timeout = parse_stop_timeout(&value.to_string_lossy())?;
+                index += 1;
+            }
+            value if value.starts_with("--stop-timeout=") => {
+                options.stop_timeout = parse_stop_timeout(&value["--stop-timeout=".len()..])?;
+                index += 1;
+            }
             "-v" | "--verbose" => {
                 options.verbose = true;
                 index += 1;
@@ -194,7 +344,7 @@ fn parse_run_options(args: Vec<OsString>) -> Result<RunCommand, String> {
         return Err("missing command after `run`".to_owned());
     };
 
-    if options.files.is_empty() {
+    if options.files.is_empty() && options.environment.is_none() {
         options.files.push(PathBuf::from(DEFAULT_FILE));
     }
 
@@ -203,13 +353,67 @@ fn parse_run_options(args: Vec<OsString>) -> Result<RunCommand, String> {
     Ok(RunCommand::Execute(options))
 }
 
+fn parse_crypt_options(name: &str, args: Vec<OsString>) -> Result<CryptCommand, String> {
+    let mut options = CryptOptions::default();
+    let mut index = 0usize;
+    while index < args.len() {
+        let token = args[index].to_string_lossy();
+        match token.as_ref() {
+            "-h" | "--help" => return Ok(CryptCommand::Help),
+            "-f" | "--file" => {
+                index += 1;
+                let Some(value) = args.get(index) else {
+                    return Err("missing value for `-f/--file`".to_owned());
+                };
+                parse_file_values(value, &mut options.files)?;
+            }
+            value if value.starts_with("--file=") => {
+                parse_file_text(&value["--file=".len()..], &mut options.files)?;
+            }
+            "-k" | "--key" => {
+                index += 1;
+                let Some(value) = args.get(index) else {
+                    return Err("missing value for `-k/--key`".to_owned());
+                };
+                parse_key_list(&value.to_string_lossy(), "-k/--key", &mut options.keys)?;
+            }
+            value if value.starts_with("--key=") => {
+                parse_key_list(&value["--key=".len()..], "-k/--key", &mut options.keys)?;
+            }
+            unknown => return Err(format!("unexpected argument `{unknown}` for `{name}`")),
+        }
+        index += 1;
+    }
+
+    if options.files.is_empty() {
+        options.files.push(PathBuf::from(DEFAULT_FILE));
+    }
+    Ok(CryptCommand::Execute(options))
+}
+
+fn parse_environment(raw: &str) -> Result<String, String> {
+    let environment = raw.trim();
+    if environment.is_empty() {
+        return Err("`-e/--env` requires an environment name".to_owned());
+    }
+    Ok(environment.to_owned())
+}
+
+fn parse_stop_timeout(raw: &str) -> Result<Duration, String> {
+    raw.trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("invalid `--stop-timeout` value `{raw}`"))
+}
+
 fn parse_file_values(raw: &OsString, files: &mut Vec<PathBuf>) -> Result<(), String> {
     parse_file_text(&raw.to_string_lossy(), files)
 }
 
 fn parse_file_text(raw: &str, files: &mut Vec<PathBuf>) -> Result<(), String> {
     let mut added = 0usize;
-    for segment in raw.split(',') {
+    for segment in split_outside_braces(raw) {
         let trimmed = segment.trim();
         if trimmed.is_empty() {
             continue;
@@ -223,39 +427,357 @@ fn parse_file_text(raw: &str, files: &mut Vec<PathBuf>) -> Result<(), String> {
     Ok(())
 }
 
+/// Split on commas that are not inside `{a,b}` glob alternatives.
+fn split_outside_braces(raw: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, ch) in raw.char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                segments.push(&raw[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&raw[start..]);
+    segments
+}
+
+fn parse_allowed_keys(raw: &str, keys: &mut Vec<String>) -> Result<(), String> {
+    parse_key_list(raw, "--allow-key", keys)
+}
+
+fn parse_key_list(raw: &str, option: &str, keys: &mut Vec<String>) -> Result<(), String> {
+    let before = keys.len();
+    keys.extend(
+        raw.split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_owned),
+    );
+    if keys.len() == before {
+        return Err(format!("`{option}` requires at least one key"));
+    }
+    Ok(())
+}
+
 fn execute_run(options: RunOptions) -> Result<i32, String> {
-    let entries = load_entries(&options).map_err(format_loader_error)?;
+    if options.watch {
+        return supervise(&options);
+    }
+    let entries = build_loader(&options)
+        .and_then(|loader| loader.parse_only())
+        .map_err(format_loader_error)?;
+    let command = build_command(
+        &options,
+        entries.into_iter().map(|entry| (entry.key, entry.value)),
+    );
+    execute_command(command, &options.command)
+}
+
+fn build_command(
+    options: &RunOptions,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Command {
     let mut command = Command::new(&options.command);
     command.args(&options.args);
 
-    for entry in entries {
-        if !options.override_existing && env::var_os(&entry.key).is_some() {
+    let protected = options
+        .allowed_keys
+        .iter()
+        .fold(ProtectedKeys::default(), |protected, key| {
+            protected.allow(KeyPattern::Glob(key.clone()))
+        });
+    for (key, value) in vars {
+        if protected.is_protected(&key) {
+            if !options.quiet {
+                eprintln!(
+                    "dotenv: warning: skipping protected key {key}; pass `--allow-key {key}` to set it"
+                );
+            }
             continue;
         }
-        command.env(entry.key, entry.value);
+        if !options.override_existing && env::var_os(&key).is_some() {
+            continue;
+        }
+        command.env(key, value);
     }
-
-    execute_command(command, &options.command)
+    command
 }
 
-fn load_entries(options: &RunOptions) -> Result<Vec<dotenvor::Entry>, Error> {
+fn build_loader(options: &RunOptions) -> Result<EnvLoader, Error> {
     let target = if options.substitution_mode == SubstitutionMode::Expand {
         TargetEnv::from_memory(snapshot_process_env()?)
     } else {
         TargetEnv::memory()
     };
 
-    let loader = EnvLoader::new()
+    let mut loader = EnvLoader::new();
+    if options.cascade {
+        loader = loader.search_bounds(SearchBounds::project());
+    }
+    if let Some(environment) = &options.environment {
+        // The stack is a set of candidates; `-i` only governs `-f` files.
+        loader = loader
+            .convention_template(Convention::default().optional(true))
+            .convention(environment);
+    }
+    Ok(loader
         .paths(&options.files)
         .required(options.required)
         .override_existing(options.override_existing)
         .search_upward(options.search_upward)
+        .cascade(options.cascade)
         .substitution_mode(options.substitution_mode)
         .key_parsing_mode(options.key_parsing_mode)
         .verbose(options.verbose)
         .quiet(options.quiet)
-        .target(target);
-    loader.parse_only()
+        .target(target))
+}
+
+/// Run the command as a child, restarting it whenever the loaded values
+/// change, until it exits on its own or a forwarded signal stops it.
+fn supervise(options: &RunOptions) -> Result<i32, String> {
+    let mut watcher = build_loader(options)
+        .and_then(EnvWatcher::new)
+        .map_err(format_loader_error)?;
+    let terminal = signals::install();
+    let mut child = spawn_command(
+        watched_command(options, &watcher),
+        terminal,
+        &options.command,
+    )?;
+    let status = loop {
+        if let Some(signal) = signals::take() {
+            break stop_child(&mut child, signal, true, options)?;
+        }
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|err| wait_error(&options.command, err))?
+        {
+            break status;
+        }
+
+        match watcher.poll() {
+            Ok(None) => {}
+            Ok(Some(_)) => {
+                if !options.quiet {
+                    eprintln!("dotenv: environment changed, restarting command");
+                }
+                stop_child(&mut child, signals::TERMINATE, false, options)?;
+                signals::reclaim_terminal(terminal);
+                child = spawn_command(
+                    watched_command(options, &watcher),
+                    terminal,
+                    &options.command,
+                )?;
+            }
+            Err(err) => eprintln!(
+                "dotenv: {}; keeping the running command",
+                format_loader_error(err)
+            ),
+        }
+        std::thread::sleep(WATCH_INTERVAL);
+    };
+    signals::reclaim_terminal(terminal);
+    Ok(exit_code(status))
+}
+
+/// Command for the watcher's latest load. With `--expand` the load starts
+/// from a snapshot of the process environment, so values the child would
+/// inherit anyway are left out.
+fn watched_command(options: &RunOptions, watcher: &EnvWatcher) -> Command {
+    let vars = watcher
+        .current()
+        .env
+        .iter()
+        .filter(|(key, value)| env::var_os(key).is_none_or(|current| current != value.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()));
+    build_command(options, vars)
+}
+
+fn spawn_command(
+    mut command: Command,
+    terminal: bool,
+    program: &OsString,
+) -> Result<Child, String> {
+    signals::isolate(&mut command, terminal);
+    command
+        .spawn()
+        .map_err(|err| format!("failed to execute `{}`: {err}", program.to_string_lossy()))
+}
+
+fn wait_error(program: &OsString, err: std::io::Error) -> String {
+    format!("failed to wait for `{}`: {err}", program.to_string_lossy())
+}
+
+/// Send `signal` to `child` and wait for it to exit, killing it once
+/// `--stop-timeout` passes. With `relay`, signals received meanwhile are
+/// forwarded too; otherwise they stay pending for the supervisor.
+fn stop_child(
+    child: &mut Child,
+    signal: i32,
+    relay: bool,
+    options: &RunOptions,
+) -> Result<ExitStatus, String> {
+    signals::forward(child, signal);
+    let deadline = Instant::now() + options.stop_timeout;
+    loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|err| wait_error(&options.command, err))?
+        {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            signals::kill(child);
+            return child
+                .wait()
+                .map_err(|err| wait_error(&options.command, err));
+        }
+        if relay && let Some(signal) = signals::take() {
+            signals::forward(child, signal);
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[cfg(unix)]
+fn exit_code(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+
+    status
+        .code()
+        .or_else(|| status.signal().map(|signal| 128 + signal))
+        .unwrap_or(1)
+}
+
+#[cfg(not(unix))]
+fn exit_code(status: ExitStatus) -> i32 {
+    status.code().unwrap_or(1)
+}
+
+/// Signals the `--watch` supervisor relays to its child.
+///
+/// The child runs in its own process group so signals sent to the group
+/// reach its descendants. When `dotenv` owns the terminal, the child's group
+/// is made the terminal's foreground group, so Ctrl-C, Ctrl-Z, and terminal
+/// reads go straight to the command; `dotenv` takes the terminal back when
+/// the command exits.
+#[cfg(unix)]
+mod signals {
+    use std::os::unix::process::CommandExt;
+    use std::process::{Child, Command};
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    pub(super) const TERMINATE: i32 = libc::SIGTERM;
+
+    static PENDING: AtomicI32 = AtomicI32::new(0);
+
+    extern "C" fn record(signum: libc::c_int) {
+        PENDING.store(signum, Ordering::SeqCst);
+    }
+
+    /// Install the handlers and report whether `dotenv` is in the
+    /// terminal's foreground process group.
+    pub(super) fn install() -> bool {
+        let handler = record as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        for signum in [libc::SIGHUP, libc::SIGINT, libc::SIGTERM] {
+            // SAFETY: `record` only stores to an atomic, which is
+            // async-signal-safe.
+            unsafe { libc::signal(signum, handler) };
+        }
+        // SAFETY: these calls only query the process and its terminal.
+        let terminal = unsafe {
+            libc::isatty(libc::STDIN_FILENO) == 1
+                && libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp()
+        };
+        if terminal {
+            // Reclaiming the terminal from the background raises SIGTTOU.
+            // SAFETY: ignoring a signal has no preconditions.
+            unsafe { libc::signal(libc::SIGTTOU, libc::SIG_IGN) };
+        }
+        terminal
+    }
+
+    pub(super) fn take() -> Option<i32> {
+        match PENDING.swap(0, Ordering::SeqCst) {
+            0 => None,
+            signum => Some(signum),
+        }
+    }
+
+    /// Start `command` in a new process group, handing it the terminal when
+    /// `terminal` is set.
+    pub(super) fn isolate(command: &mut Command, terminal: bool) {
+        command.process_group(0);
+        if !terminal {
+            return;
+        }
+        // SAFETY: the closure only makes async-signal-safe calls. It runs
+        // after the child joined its new group and before `exec`, so the
+        // command never reads from a terminal it does not own.
+        unsafe {
+            command.pre_exec(|| {
+                if libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp()) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                libc::signal(libc::SIGTTOU, libc::SIG_DFL);
+                Ok(())
+            });
+        }
+    }
+
+    /// Make `dotenv`'s group the terminal's foreground group again.
+    pub(super) fn reclaim_terminal(terminal: bool) {
+        if terminal {
+            // SAFETY: `tcsetpgrp` has no memory-safety preconditions.
+            unsafe { libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp()) };
+        }
+    }
+
+    /// Send `signum` to the child's process group.
+    pub(super) fn forward(child: &mut Child, signum: i32) {
+        // SAFETY: `kill` has no memory-safety preconditions; the child leads
+        // its own group, so this cannot reach unrelated processes.
+        unsafe { libc::kill(-(child.id() as libc::pid_t), signum) };
+    }
+
+    pub(super) fn kill(child: &mut Child) {
+        forward(child, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+mod signals {
+    use std::process::{Child, Command};
+
+    pub(super) const TERMINATE: i32 = 0;
+
+    pub(super) fn install() -> bool {
+        false
+    }
+
+    pub(super) fn take() -> Option<i32> {
+        None
+    }
+
+    pub(super) fn isolate(_command: &mut Command, _terminal: bool) {}
+
+    pub(super) fn reclaim_terminal(_terminal: bool) {}
+
+    /// Without signals, any stop request kills the child.
+    pub(super) fn forward(child: &mut Child, _signum: i32) {
+        kill(child);
+    }
+
+    pub(super) fn kill(child: &mut Child) {
+        let _ = child.kill();
+    }
 }
 
 fn snapshot_process_env() -> Result<BTreeMap<String, String>, Error> {
@@ -297,11 +819,54 @@ fn execute_command(mut command: Command, program: &OsString) -> Result<i32, Stri
     Ok(status.code().unwrap_or(1))
 }
 
+#[cfg(feature = "encryption")]
+fn execute_crypt(encrypt: bool, options: CryptOptions) -> Result<(), String> {
+    let keys = options
+        .keys
+        .iter()
+        .map(|key| KeyPattern::Glob(key.clone()))
+        .collect::<Vec<_>>();
+    for file in &options.files {
+        if encrypt {
+            let report = dotenvor::encrypt_file(file, &keys).map_err(format_loader_error)?;
+            if let Some(keys_file) = &report.keys_file {
+                eprintln!(
+                    "dotenv: generated a key pair for {}; keep {} out of version control",
+                    file.display(),
+                    keys_file.display()
+                );
+            }
+            eprintln!(
+                "dotenv: encrypted {} value(s) in {}",
+                report.encrypted,
+                file.display()
+            );
+        } else {
+            let decrypted = dotenvor::decrypt_file(file, &keys).map_err(format_loader_error)?;
+            eprintln!(
+                "dotenv: decrypted {decrypted} value(s) in {}",
+                file.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "encryption"))]
+fn execute_crypt(encrypt: bool, _options: CryptOptions) -> Result<(), String> {
+    Err(format!(
+        "`dotenv {}` requires dotenvor built with the `encryption` feature",
+        if encrypt { "encrypt" } else { "decrypt" }
+    ))
+}
+
 fn format_loader_error(err: Error) -> String {
     match err {
         Error::Io(io_err) => format!("I/O error: {io_err}"),
         Error::Parse(parse_err) => parse_err.to_string(),
         Error::InvalidEncoding(utf8_err) => format!("invalid UTF-8 input: {utf8_err}"),
+        Error::Validation(validation_err) => validation_err.to_string(),
+        Error::Encryption(encryption_err) => encryption_err.to_string(),
     }
 }
 
@@ -323,6 +888,7 @@ mod tests {
     use dotenvor::{KeyParsingMode, SubstitutionMode};
     use std::ffi::OsString;
     use std::path::PathBuf;
+    use std::time::Duration;
 
     #[test]
     fn parse_run_uses_defaults() {
@@ -336,6 +902,7 @@ mod tests {
         assert!(options.required);
         assert!(!options.override_existing);
         assert!(!options.search_upward);
+        assert!(!options.cascade);
         assert_eq!(options.substitution_mode, SubstitutionMode::Disabled);
         assert_eq!(options.key_parsing_mode, KeyParsingMode::Strict);
         assert_eq!(options.command, OsString::from("printenv"));
@@ -368,6 +935,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_run_keeps_brace_alternatives_together() {
+        let parsed = parse_run_options(vec![
+            OsString::from("-f"),
+            OsString::from(".env.{shared,local},config/*.env"),
+            OsString::from("printenv"),
+        ])
+        .expect("parse should succeed");
+        let RunCommand::Execute(options) = parsed else {
+            panic!("expected execute");
+        };
+
+        assert_eq!(
+            options.files,
+            vec![
+                PathBuf::from(".env.{shared,local}"),
+                PathBuf::from("config/*.env"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_run_env_replaces_default_file() {
+        let parsed = parse_run_options(vec![
+            OsString::from("-e"),
+            OsString::from("production"),
+            OsString::from("printenv"),
+        ])
+        .expect("parse should succeed");
+        let RunCommand::Execute(options) = parsed else {
+            panic!("expected execute");
+        };
+
+        assert_eq!(options.environment.as_deref(), Some("production"));
+        assert!(options.files.is_empty());
+        assert!(parse_run_options(vec![OsString::from("--env="), OsString::from("x")]).is_err());
+    }
+
     #[test]
     fn parse_run_reports_missing_file_value() {
         let err = parse_run_options(vec![OsString::from("-f")]).expect_err("parse should fail");
@@ -392,11 +997,51 @@ mod tests {
         assert_eq!(parsed, RunCommand::Help);
     }
 
+    #[test]
+    fn parse_run_collects_allowed_keys() {
+        let parsed = parse_run_options(vec![
+            OsString::from("--allow-key"),
+            OsString::from("PATH, LD_*"),
+            OsString::from("--allow-key=HOME"),
+            OsString::from("printenv"),
+        ])
+        .expect("parse should succeed");
+        let RunCommand::Execute(options) = parsed else {
+            panic!("expected execute");
+        };
+
+        assert_eq!(options.allowed_keys, vec!["PATH", "LD_*", "HOME"]);
+    }
+
+    #[test]
+    fn parse_run_supports_watch_and_stop_timeout() {
+        let parsed = parse_run_options(vec![
+            OsString::from("--watch"),
+            OsString::from("--stop-timeout=2.5"),
+            OsString::from("printenv"),
+        ])
+        .expect("parse should succeed");
+        let RunCommand::Execute(options) = parsed else {
+            panic!("expected execute");
+        };
+
+        assert!(options.watch);
+        assert_eq!(options.stop_timeout, Duration::from_millis(2500));
+        let err = parse_run_options(vec![
+            OsString::from("--stop-timeout"),
+            OsString::from("-1"),
+            OsString::from("printenv"),
+        ])
+        .expect_err("negative timeouts should be rejected");
+        assert_eq!(err, "invalid `--stop-timeout` value `-1`");
+    }
+
     #[test]
     fn run_options_default_matches_expected_behavior() {
         let options = RunOptions::default();
         assert!(options.required);
         assert!(!options.override_existing);
         assert!(!options.search_upward);
+        assert!(!options.watch);
     }
 }
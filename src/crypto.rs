@@ -0,0 +1,528 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::error::{EncryptionError, Error};
+use crate::model::{Entry, KeyParsingMode};
+use crate::parser::{normalize_newlines, parse_line, parse_str, statements};
+use crate::pattern::KeyPattern;
+use crate::secret::SecretString;
+use crate::substitution::{decode_base64, encode_base64};
+
+/// Prefix marking an encrypted value.
+///
+/// The layout borrows dotenvx's `encrypted:` prefix, public-key header, and
+/// `.env.keys` file, but the ciphertext is not dotenvx-compatible: dotenvx
+/// uses secp256k1 ECIES, while values here use X25519 and ChaCha20-Poly1305.
+pub const ENCRYPTED_PREFIX: &str = "encrypted:";
+
+/// File holding private keys, next to the encrypted dotenv file.
+pub const KEYS_FILE: &str = ".env.keys";
+
+const PUBLIC_KEY_VAR: &str = "DOTENV_PUBLIC_KEY";
+const PRIVATE_KEY_VAR: &str = "DOTENV_PRIVATE_KEY";
+const HKDF_INFO: &[u8] = b"dotenvor encrypted value v1";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// An X25519 key pair, hex-encoded.
+///
+/// The public key encrypts values and is stored in the dotenv file; the
+/// private key decrypts them and belongs in `.env.keys` or the environment.
+#[derive(Debug, Clone)]
+pub struct KeyPair {
+    pub public_key: String,
+    pub private_key: SecretString,
+}
+
+impl KeyPair {
+    pub fn generate() -> Result<Self, Error> {
+        let secret = StaticSecret::from(random_bytes::<KEY_LEN>()?);
+        Ok(Self {
+            public_key: encode_hex(PublicKey::from(&secret).as_bytes()),
+            private_key: SecretString::new(encode_hex(secret.as_bytes())),
+        })
+    }
+}
+
+/// Summary of [`encrypt_file`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EncryptReport {
+    /// Number of values encrypted.
+    pub encrypted: usize,
+    /// Keys file a newly generated private key was written to.
+    pub keys_file: Option<PathBuf>,
+}
+
+/// Encrypt `value` for `public_key`, binding it to the variable name `key`.
+///
+/// Each call uses a fresh ephemeral key, so the output differs between calls.
+pub fn encrypt_value(public_key: &str, key: &str, value: &str) -> Result<String, Error> {
+    let recipient = PublicKey::from(decode_key(public_key, Some(key), "public")?);
+    let ephemeral = StaticSecret::from(random_bytes::<KEY_LEN>()?);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let cipher = value_cipher(&ephemeral, &recipient, &ephemeral_public, &recipient, key)?;
+    let nonce = random_bytes::<NONCE_LEN>()?;
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: value.as_bytes(),
+                aad: key.as_bytes(),
+            },
+        )
+        .map_err(|_| EncryptionError::new(Some(key), "encryption failed"))?;
+
+    let mut payload = Vec::with_capacity(KEY_LEN + NONCE_LEN + ciphertext.len());
+    payload.extend_from_slice(ephemeral_public.as_bytes());
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&ciphertext);
+    Ok(format!("{ENCRYPTED_PREFIX}{}", encode_base64(&payload)))
+}
+
+/// Decrypt a value produced by [`encrypt_value`] for the variable `key`.
+pub fn decrypt_value(private_key: &str, key: &str, value: &str) -> Result<String, Error> {
+    let invalid = || EncryptionError::new(Some(key), "malformed encrypted value");
+    let encoded = value.strip_prefix(ENCRYPTED_PREFIX).ok_or_else(invalid)?;
+    let payload = decode_base64(encoded).ok_or_else(invalid)?;
+    if payload.len() < KEY_LEN + NONCE_LEN {
+        return Err(invalid().into());
+    }
+    let (ephemeral_public, rest) = payload.split_at(KEY_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let ephemeral_public = PublicKey::from(<[u8; KEY_LEN]>::try_from(ephemeral_public).unwrap());
+
+    let secret = StaticSecret::from(decode_key(private_key, Some(key), "private")?);
+    let recipient = PublicKey::from(&secret);
+    let cipher = value_cipher(
+        &secret,
+        &ephemeral_public,
+        &ephemeral_public,
+        &recipient,
+        key,
+    )?;
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: key.as_bytes(),
+            },
+        )
+        .map_err(|_| {
+            EncryptionError::new(
+                Some(key),
+                "decryption failed; wrong private key or tampered value",
+            )
+        })?;
+    String::from_utf8(plaintext)
+        .map_err(|_| EncryptionError::new(Some(key), "decrypted value is not valid UTF-8").into())
+}
+
+/// Encrypt the plaintext values of the dotenv file at `path` in place.
+///
+/// Only keys matching one of `keys` are encrypted; an empty slice encrypts
+/// every value. When the file has no `DOTENV_PUBLIC_KEY*` entry, a key pair
+/// is generated: the public key is prepended to the file and the private key
+/// appended to `.env.keys` in the same directory. Rewritten lines lose
+/// trailing comments, and line endings are normalized to `\n`.
+pub fn encrypt_file(path: impl AsRef<Path>, keys: &[KeyPattern]) -> Result<EncryptReport, Error> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)?;
+    let public_var = key_var(PUBLIC_KEY_VAR, path);
+    let existing = parse_str(&content)?
+        .into_iter()
+        .find(|entry| entry.key == public_var)
+        .map(|entry| entry.value);
+
+    let mut report = EncryptReport::default();
+    let generated = match existing {
+        Some(_) => None,
+        None => Some(KeyPair::generate()?),
+    };
+    let public_key = match (&existing, &generated) {
+        (Some(public_key), _) => public_key.as_str(),
+        (None, Some(pair)) => pair.public_key.as_str(),
+        (None, None) => unreachable!("a key pair is generated when none exists"),
+    };
+
+    let mut rewritten = rewrite_values(&content, |key, value| {
+        if key.starts_with(PUBLIC_KEY_VAR)
+            || value.starts_with(ENCRYPTED_PREFIX)
+            || !(keys.is_empty() || keys.iter().any(|pattern| pattern.matches(key)))
+        {
+            return Ok(None);
+        }
+        report.encrypted += 1;
+        encrypt_value(public_key, key, value).map(|value| Some(format!("\"{value}\"")))
+    })?;
+    let Some(pair) = generated else {
+        std::fs::write(path, rewritten)?;
+        return Ok(report);
+    };
+
+    // Stage the rewrite before saving the private key, so a failed write
+    // leaves neither an orphan key nor values encrypted for a lost key.
+    rewritten.insert_str(0, &format!("{public_var}=\"{}\"\n\n", pair.public_key));
+    let staged = staging_path(path);
+    std::fs::write(&staged, rewritten)?;
+    let keys_file = keys_file(path);
+    if let Err(err) = append_private_key(
+        &keys_file,
+        &key_var(PRIVATE_KEY_VAR, path),
+        &pair.private_key,
+    ) {
+        let _ = std::fs::remove_file(&staged);
+        return Err(err);
+    }
+    std::fs::rename(&staged, path)?;
+    report.keys_file = Some(keys_file);
+    Ok(report)
+}
+
+/// Sibling of `path` used to stage a rewrite before renaming it into place.
+fn staging_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".dotenvor-tmp");
+    path.with_file_name(name)
+}
+
+/// Decrypt the encrypted values of the dotenv file at `path` in place.
+///
+/// Only keys matching one of `keys` are decrypted; an empty slice decrypts
+/// every value. Returns the number of values decrypted.
+pub fn decrypt_file(path: impl AsRef<Path>, keys: &[KeyPattern]) -> Result<usize, Error> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)?;
+    let mut private_key = None;
+    let mut decrypted = 0;
+    let rewritten = rewrite_values(&content, |key, value| {
+        if !value.starts_with(ENCRYPTED_PREFIX)
+            || !(keys.is_empty() || keys.iter().any(|pattern| pattern.matches(key)))
+        {
+            return Ok(None);
+        }
+        if private_key.is_none() {
+            private_key = Some(require_private_key(path, key)?);
+        }
+        let private_key = private_key.as_ref().unwrap();
+        decrypted += 1;
+        decrypt_value(private_key.expose_secret(), key, value).map(|value| Some(quote(&value)))
+    })?;
+    if decrypted > 0 {
+        std::fs::write(path, rewritten)?;
+    }
+    Ok(decrypted)
+}
+
+/// Decrypt encrypted entries read from `path` and mark them secret.
+///
+/// Decrypted values are literal; when `escape_dollars` is set, `$` is escaped
+/// so later substitution leaves it alone.
+pub(crate) fn decrypt_entries(
+    entries: &mut [Entry],
+    path: &Path,
+    private_key: Option<&SecretString>,
+    escape_dollars: bool,
+) -> Result<(), Error> {
+    let Some(first) = entries
+        .iter()
+        .find(|entry| entry.value.starts_with(ENCRYPTED_PREFIX))
+    else {
+        return Ok(());
+    };
+    let found;
+    let private_key = match private_key {
+        Some(private_key) => private_key,
+        None => {
+            found = require_private_key(path, &first.key)?;
+            &found
+        }
+    };
+
+    for entry in entries
+        .iter_mut()
+        .filter(|entry| entry.value.starts_with(ENCRYPTED_PREFIX))
+    {
+        let value = decrypt_value(private_key.expose_secret(), &entry.key, &entry.value)?;
+        entry.value = if escape_dollars {
+            value.replace('$', "\\$")
+        } else {
+            value
+        };
+        entry.secret = true;
+    }
+    Ok(())
+}
+
+/// Find the private key for `path`.
+///
+/// Looks up `DOTENV_PRIVATE_KEY_<SUFFIX>` and `DOTENV_PRIVATE_KEY` in the
+/// process environment, then the same names in `.env.keys` next to `path`.
+fn find_private_key(path: &Path) -> Result<Option<SecretString>, Error> {
+    let specific = key_var(PRIVATE_KEY_VAR, path);
+    let names = [specific.as_str(), PRIVATE_KEY_VAR];
+    for name in names {
+        if let Some(value) = std::env::var_os(name) {
+            return Ok(Some(SecretString::new(value.to_string_lossy())));
+        }
+    }
+
+    let content = match std::fs::read_to_string(keys_file(path)) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    // `encrypt_file` appends a new key when re-keying, so the last one wins.
+    let entries = parse_str(&content)?;
+    Ok(names.iter().find_map(|name| {
+        entries
+            .iter()
+            .rev()
+            .find(|entry| entry.key == *name)
+            .map(|entry| SecretString::new(entry.value.as_str()))
+    }))
+}
+
+fn require_private_key(path: &Path, key: &str) -> Result<SecretString, Error> {
+    find_private_key(path)?.ok_or_else(|| {
+        EncryptionError::new(
+            Some(key),
+            format!(
+                "no private key for {}; set {} or add it to {KEYS_FILE}",
+                path.display(),
+                key_var(PRIVATE_KEY_VAR, path)
+            ),
+        )
+        .into()
+    })
+}
+
+/// `base` suffixed with the environment of `path`, e.g.
+/// `DOTENV_PUBLIC_KEY_PRODUCTION` for `.env.production`.
+fn key_var(base: &str, path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    match name.strip_prefix(".env.") {
+        Some(suffix) if !suffix.is_empty() => {
+            let suffix = suffix
+                .chars()
+                .map(|ch| {
+                    if ch.is_ascii_alphanumeric() {
+                        ch.to_ascii_uppercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>();
+            format!("{base}_{suffix}")
+        }
+        _ => base.to_owned(),
+    }
+}
+
+fn keys_file(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.join(KEYS_FILE),
+        _ => PathBuf::from(KEYS_FILE),
+    }
+}
+
+fn append_private_key(
+    keys_file: &Path,
+    var: &str,
+    private_key: &SecretString,
+) -> Result<(), Error> {
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(keys_file)?;
+    writeln!(file, "{var}=\"{}\"", private_key.expose_secret())?;
+    Ok(())
+}
+
+/// Replace the values `replace` returns a new raw value for, keeping the rest
+/// of `content` as written.
+fn rewrite_values(
+    content: &str,
+    mut replace: impl FnMut(&str, &str) -> Result<Option<String>, Error>,
+) -> Result<String, Error> {
+    let normalized = normalize_newlines(content);
+    let content = normalized.as_ref();
+    let mut out = String::with_capacity(content.len());
+    let mut copied = 0;
+    for statement in statements(content) {
+        let Some(entry) = parse_line(
+            statement.text,
+            statement.line,
+            None,
+            KeyParsingMode::Strict,
+            None,
+        )?
+        else {
+            continue;
+        };
+        let Some(value) = replace(&entry.key, &entry.value)? else {
+            continue;
+        };
+
+        let Some(eq) = statement.text.find('=') else {
+            continue;
+        };
+        let key_start = statement.text[..eq].trim_end().len() - entry.key.len();
+        out.push_str(&content[copied..statement.start]);
+        out.push_str(&statement.text[..key_start]);
+        out.push_str(&entry.key);
+        out.push('=');
+        out.push_str(&value);
+        copied = statement.start + statement.text.len();
+    }
+    out.push_str(&content[copied..]);
+    Ok(out)
+}
+
+/// Quote `value` so it parses back literally.
+fn quote(value: &str) -> String {
+    if !value.contains('\'') && !value.ends_with('\\') {
+        return format!("'{value}'");
+    }
+
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '\\' | '"' | '$' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\r' => out.push_str("\\r"),
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Derive the cipher for one value from the X25519 exchange between
+/// `secret` and `peer`.
+fn value_cipher(
+    secret: &StaticSecret,
+    peer: &PublicKey,
+    ephemeral_public: &PublicKey,
+    recipient: &PublicKey,
+    key: &str,
+) -> Result<ChaCha20Poly1305, Error> {
+    let shared = secret.diffie_hellman(peer);
+    if !shared.was_contributory() {
+        return Err(EncryptionError::new(Some(key), "invalid public key").into());
+    }
+    let mut salt = [0u8; 2 * KEY_LEN];
+    salt[..KEY_LEN].copy_from_slice(ephemeral_public.as_bytes());
+    salt[KEY_LEN..].copy_from_slice(recipient.as_bytes());
+
+    let mut okm = [0u8; KEY_LEN];
+    Hkdf::<Sha256>::new(Some(&salt), shared.as_bytes())
+        .expand(HKDF_INFO, &mut okm)
+        .map_err(|_| EncryptionError::new(Some(key), "key derivation failed"))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&okm)))
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], Error> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|err| {
+        EncryptionError::new(None, format!("random number generator failed: {err}"))
+    })?;
+    Ok(bytes)
+}
+
+fn decode_key(hex: &str, key: Option<&str>, kind: &str) -> Result<[u8; KEY_LEN], Error> {
+    decode_hex(hex.trim())
+        .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+        .ok_or_else(|| EncryptionError::new(key, format!("invalid {kind} key")).into())
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push(DIGITS[usize::from(byte >> 4)] as char);
+        out.push(DIGITS[usize::from(byte & 0x0f)] as char);
+    }
+    out
+}
+
+fn decode_hex(input: &str) -> Option<Vec<u8>> {
+    if !input.len().is_multiple_of(2) {
+        return None;
+    }
+    input
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{KeyPair, decrypt_value, encrypt_value, key_var, quote};
+    use crate::parser::parse_str;
+
+    #[test]
+    fn values_round_trip_and_are_bound_to_their_key() {
+        let pair = KeyPair::generate().expect("key pair should generate");
+        let private_key = pair.private_key.expose_secret();
+        let encrypted =
+            encrypt_value(&pair.public_key, "API_TOKEN", "s3cr3t").expect("value should encrypt");
+        assert!(encrypted.starts_with("encrypted:"));
+        assert_ne!(
+            encrypted,
+            encrypt_value(&pair.public_key, "API_TOKEN", "s3cr3t").unwrap()
+        );
+
+        assert_eq!(
+            decrypt_value(private_key, "API_TOKEN", &encrypted).unwrap(),
+            "s3cr3t"
+        );
+        assert!(decrypt_value(private_key, "OTHER_TOKEN", &encrypted).is_err());
+
+        let other = KeyPair::generate().unwrap();
+        assert!(decrypt_value(other.private_key.expose_secret(), "API_TOKEN", &encrypted).is_err());
+    }
+
+    #[test]
+    fn key_vars_follow_the_file_environment() {
+        assert_eq!(
+            key_var("DOTENV_PUBLIC_KEY", Path::new("dir/.env")),
+            "DOTENV_PUBLIC_KEY"
+        );
+        assert_eq!(
+            key_var("DOTENV_PRIVATE_KEY", Path::new(".env.production.local")),
+            "DOTENV_PRIVATE_KEY_PRODUCTION_LOCAL"
+        );
+    }
+
+    #[test]
+    fn quoted_values_parse_back_literally() {
+        for value in ["plain $HOME", "it's \"quoted\" $X", "ends with \\"] {
+            let entries = parse_str(&format!("KEY={}", quote(value))).unwrap();
+            assert_eq!(entries[0].value, value);
+        }
+    }
+}
@@ -0,0 +1,511 @@
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::fmt::{Display, Formatter};
+
+use serde::de::value::StrDeserializer;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+
+use crate::model::{LoadedEnv, Origin};
+use crate::schema::parse_bool;
+use crate::secret::{REDACTED, is_default_secret};
+
+/// Separator between a key's segments when it targets a nested struct field.
+const NESTING_SEPARATOR: &str = "__";
+
+/// Deserialize the current process environment into `T`.
+///
+/// Keys match fields case-insensitively and `__` selects nested struct
+/// fields, so `DB__PORT` fills `db.port`. Non-UTF-8 variables are skipped.
+pub fn from_env<T: DeserializeOwned>() -> Result<T, DeserializeError> {
+    from_env_prefixed("")
+}
+
+/// Like [`from_env`], but only considers keys starting with `prefix`
+/// (case-insensitive) and strips it before matching fields.
+pub fn from_env_prefixed<T: DeserializeOwned>(prefix: &str) -> Result<T, DeserializeError> {
+    let vars = std::env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+        .collect::<Vec<_>>();
+    let root = Node::build(
+        vars.iter()
+            .map(|(key, value)| (key.as_str(), value.as_str(), None)),
+        prefix,
+        is_default_secret,
+    );
+    T::deserialize(NodeDeserializer { node: &root })
+}
+
+impl LoadedEnv {
+    /// Deserialize the loaded variables into `T`.
+    ///
+    /// See [`from_env`] for how keys map to fields. Errors name the offending
+    /// key and, when it was read from a file, its path and line.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, DeserializeError> {
+        self.deserialize_prefixed("")
+    }
+
+    /// Like [`LoadedEnv::deserialize`], but only considers keys starting with
+    /// `prefix` (case-insensitive) and strips it before matching fields.
+    pub fn deserialize_prefixed<T: DeserializeOwned>(
+        &self,
+        prefix: &str,
+    ) -> Result<T, DeserializeError> {
+        let root = Node::build(
+            self.env
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str(), self.origins.get(key.as_str()))),
+            prefix,
+            |key| self.is_secret(key),
+        );
+        T::deserialize(NodeDeserializer { node: &root })
+    }
+}
+
+/// Failure to deserialize variables into a config type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeserializeError {
+    /// Variable the error relates to, when known.
+    pub key: Option<String>,
+    /// Where `key` was read from, when it came from a file.
+    pub origin: Option<Origin>,
+    pub message: String,
+}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(key) = &self.key {
+            write!(f, "{key}")?;
+            if let Some(origin) = &self.origin {
+                write!(f, " ({}:{})", origin.path.display(), origin.line)?;
+            }
+            f.write_str(": ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for DeserializeError {}
+
+impl de::Error for DeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self {
+            key: None,
+            origin: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Variables grouped by `__`-separated key segments.
+#[derive(Default)]
+struct Node<'a> {
+    /// Key prefix (or full key, for values) this node was reached by.
+    path: String,
+    value: Option<&'a str>,
+    origin: Option<&'a Origin>,
+    /// Whether `value` must not appear in error messages.
+    secret: bool,
+    /// Children by lowercased segment, keeping the segment as first seen.
+    children: BTreeMap<String, (&'a str, Node<'a>)>,
+}
+
+impl<'a> Node<'a> {
+    fn build(
+        vars: impl Iterator<Item = (&'a str, &'a str, Option<&'a Origin>)>,
+        prefix: &str,
+        is_secret: impl Fn(&str) -> bool,
+    ) -> Self {
+        let mut root = Self::default();
+        for (key, value, origin) in vars {
+            let Some(rest) = strip_prefix_ignore_case(key, prefix) else {
+                continue;
+            };
+            if rest.is_empty() {
+                continue;
+            }
+
+            let mut node = &mut root;
+            let mut consumed = key.len() - rest.len();
+            for segment in rest.split(NESTING_SEPARATOR) {
+                consumed += segment.len();
+                let path = &key[..consumed];
+                consumed += NESTING_SEPARATOR.len();
+                node = &mut node
+                    .children
+                    .entry(segment.to_lowercase())
+                    .or_insert_with(|| {
+                        (
+                            segment,
+                            Node {
+                                path: path.to_owned(),
+                                ..Node::default()
+                            },
+                        )
+                    })
+                    .1;
+            }
+            node.path = key.to_owned();
+            node.value = Some(value);
+            node.origin = origin;
+            node.secret = is_secret(key);
+        }
+        root
+    }
+
+    fn error(&self, message: impl Display) -> DeserializeError {
+        DeserializeError {
+            key: (!self.path.is_empty()).then(|| self.path.clone()),
+            origin: self.origin.cloned(),
+            message: message.to_string(),
+        }
+    }
+
+    fn invalid_value(&self, value: &str, reason: impl Display) -> DeserializeError {
+        let value = if self.secret { REDACTED } else { value };
+        self.error(format!("invalid value `{value}`: {reason}"))
+    }
+
+    /// Attach this node's key to errors raised without one.
+    fn locate(&self, mut err: DeserializeError) -> DeserializeError {
+        if err.key.is_none() && !self.path.is_empty() {
+            err.key = Some(self.path.clone());
+            err.origin = self.origin.cloned();
+        }
+        err
+    }
+}
+
+fn strip_prefix_ignore_case<'k>(key: &'k str, prefix: &str) -> Option<&'k str> {
+    let head = key.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &key[prefix.len()..])
+}
+
+struct NodeDeserializer<'n, 'a> {
+    node: &'n Node<'a>,
+}
+
+impl NodeDeserializer<'_, '_> {
+    fn value(&self) -> Result<&str, DeserializeError> {
+        self.node
+            .value
+            .ok_or_else(|| self.node.error("expected a value, found only nested keys"))
+    }
+
+    fn parse<T>(&self) -> Result<T, DeserializeError>
+    where
+        T: std::str::FromStr,
+        T::Err: Display,
+    {
+        let value = self.value()?;
+        value
+            .trim()
+            .parse()
+            .map_err(|err| self.node.invalid_value(value, err))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for NodeDeserializer<'_, '_> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.node.value {
+            Some(value) if self.node.children.is_empty() => visitor.visit_str(value),
+            _ => self.deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let value = self.value()?;
+        match parse_bool(value.trim()) {
+            Some(parsed) => visitor.visit_bool(parsed),
+            None => Err(self.node.invalid_value(value, "expected a bool")),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str(self.value()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bytes(self.value()?.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // Absent keys never reach here: serde fills missing `Option` fields
+        // with `None` itself.
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Sequences are comma-separated; an empty value is an empty sequence.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let value = self.value()?;
+        let items = if value.trim().is_empty() {
+            Vec::new()
+        } else {
+            value
+                .split(',')
+                .map(|item| Node {
+                    path: self.node.path.clone(),
+                    value: Some(item.trim()),
+                    origin: self.node.origin,
+                    secret: self.node.secret,
+                    children: BTreeMap::new(),
+                })
+                .collect()
+        };
+        visitor.visit_seq(ItemsAccess {
+            items: items.iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(ChildrenAccess {
+            children: self.node.children.values(),
+            fields: &[],
+            pending: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor
+            .visit_map(ChildrenAccess {
+                children: self.node.children.values(),
+                fields,
+                pending: None,
+            })
+            .map_err(|err| self.node.locate(err))
+    }
+
+    /// Unit variants are selected by name, ignoring case.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value = self.value()?.trim();
+        let variant = variants
+            .iter()
+            .find(|variant| variant.eq_ignore_ascii_case(value))
+            .copied()
+            .unwrap_or(value);
+        let access: StrDeserializer<'_, DeserializeError> = variant.into_deserializer();
+        visitor
+            .visit_enum(access)
+            .map_err(|err| self.node.locate(err))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct ChildrenAccess<'n, 'a> {
+    children: std::collections::btree_map::Values<'n, String, (&'a str, Node<'a>)>,
+    fields: &'static [&'static str],
+    pending: Option<&'n Node<'a>>,
+}
+
+impl<'de> MapAccess<'de> for ChildrenAccess<'_, '_> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((segment, node)) = self.children.next() else {
+            return Ok(None);
+        };
+        self.pending = Some(node);
+        let name = self
+            .fields
+            .iter()
+            .find(|field| field.eq_ignore_ascii_case(segment))
+            .copied()
+            .unwrap_or(segment);
+        let key: StrDeserializer<'_, DeserializeError> = name.into_deserializer();
+        seed.deserialize(key)
+            .map(Some)
+            .map_err(|err| node.locate(err))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let node = self
+            .pending
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(NodeDeserializer { node })
+            .map_err(|err| node.locate(err))
+    }
+}
+
+struct ItemsAccess<'n, 'a> {
+    items: std::slice::Iter<'n, Node<'a>>,
+}
+
+impl<'de> SeqAccess<'de> for ItemsAccess<'_, '_> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let Some(node) = self.items.next() else {
+            return Ok(None);
+        };
+        seed.deserialize(NodeDeserializer { node }).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use serde::Deserialize;
+
+    use super::Node;
+    use crate::model::{LoadReport, LoadedEnv};
+
+    fn loaded(vars: &[(&str, &str)]) -> LoadedEnv {
+        LoadedEnv {
+            report: LoadReport::default(),
+            env: vars
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            origins: BTreeMap::new(),
+            secrets: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn builds_nested_nodes_with_original_paths() {
+        let root = Node::build(
+            [("APP_DB__HOST", "db", None), ("APP_DB__PORT", "5432", None)].into_iter(),
+            "app_",
+            |_| false,
+        );
+        let (segment, db) = &root.children["db"];
+        assert_eq!(*segment, "DB");
+        assert_eq!(db.path, "APP_DB");
+        assert_eq!(db.children["port"].1.path, "APP_DB__PORT");
+        assert_eq!(db.children["port"].1.value, Some("5432"));
+    }
+
+    #[test]
+    fn deserializes_maps_tuples_and_unit_enums() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        #[serde(rename_all = "lowercase")]
+        enum Mode {
+            Fast,
+            Safe,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Config {
+            mode: Mode,
+            pair: (u8, char),
+            labels: BTreeMap<String, String>,
+        }
+
+        let config = loaded(&[("MODE", "SAFE"), ("PAIR", "7, x"), ("LABELS__Team", "core")])
+            .deserialize::<Config>()
+            .expect("deserialize should succeed");
+
+        assert_eq!(
+            config,
+            Config {
+                mode: Mode::Safe,
+                pair: (7, 'x'),
+                labels: BTreeMap::from([("Team".to_string(), "core".to_string())]),
+            }
+        );
+        assert_ne!(config.mode, Mode::Fast);
+    }
+}
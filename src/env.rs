@@ -1,6 +1,94 @@
 use std::collections::BTreeMap;
 use std::io::{Error as IoError, ErrorKind};
 
+use crate::pattern::KeyPattern;
+
+/// Keys that would let a dotenv file hijack the shell, dynamic linker, or
+/// interpreters started from the process.
+const DEFAULT_PROTECTED_KEYS: &[&str] = &[
+    "PATH",
+    "SHELL",
+    "IFS",
+    "BASH_ENV",
+    "SHELLOPTS",
+    "PS4",
+    "PROMPT_COMMAND",
+    "NODE_OPTIONS",
+    "PYTHONSTARTUP",
+    "PERL5OPT",
+];
+
+const DEFAULT_PROTECTED_PREFIXES: &[&str] = &["LD_", "DYLD_", "BASH_FUNC_"];
+
+/// Keys a [`TargetEnv`] refuses to set.
+///
+/// [`ProtectedKeys::default`] guards `PATH`, `LD_*`, `DYLD_*`, and
+/// similar keys. Allowed patterns take precedence over denied ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectedKeys {
+    denied: Vec<KeyPattern>,
+    allowed: Vec<KeyPattern>,
+    strict: bool,
+}
+
+impl Default for ProtectedKeys {
+    fn default() -> Self {
+        let denied = DEFAULT_PROTECTED_KEYS
+            .iter()
+            .map(|key| KeyPattern::Glob((*key).to_owned()))
+            .chain(
+                DEFAULT_PROTECTED_PREFIXES
+                    .iter()
+                    .map(|prefix| KeyPattern::Prefix((*prefix).to_owned())),
+            )
+            .collect();
+        Self {
+            denied,
+            allowed: Vec::new(),
+            strict: false,
+        }
+    }
+}
+
+impl ProtectedKeys {
+    /// Protect nothing.
+    pub fn none() -> Self {
+        Self {
+            denied: Vec::new(),
+            allowed: Vec::new(),
+            strict: false,
+        }
+    }
+
+    pub fn deny(mut self, pattern: KeyPattern) -> Self {
+        self.denied.push(pattern);
+        self
+    }
+
+    /// Permit keys matching `pattern` even when a denied pattern matches.
+    pub fn allow(mut self, pattern: KeyPattern) -> Self {
+        self.allowed.push(pattern);
+        self
+    }
+
+    /// Fail the load instead of skipping protected keys.
+    ///
+    /// Defaults to `false`.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn is_protected(&self, key: &str) -> bool {
+        self.denied.iter().any(|pattern| pattern.matches(key))
+            && !self.allowed.iter().any(|pattern| pattern.matches(key))
+    }
+}
+
 /// Destination for loaded environment variables.
 ///
 /// This type intentionally does not implement [`Clone`]. Cloning a process
@@ -9,6 +97,7 @@ use std::io::{Error as IoError, ErrorKind};
 #[derive(Debug, PartialEq, Eq)]
 pub struct TargetEnv {
     kind: TargetEnvKind,
+    protected: ProtectedKeys,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -31,6 +120,9 @@ impl Default for TargetEnv {
 impl TargetEnv {
     /// Create a process-environment target.
     ///
+    /// Process targets refuse [`ProtectedKeys::default`]; adjust this with
+    /// [`TargetEnv::protected_keys`].
+    ///
     /// # Safety
     ///
     /// The caller must ensure no other threads concurrently read or write the
@@ -39,6 +131,7 @@ impl TargetEnv {
     pub unsafe fn process() -> Self {
         Self {
             kind: TargetEnvKind::Process,
+            protected: ProtectedKeys::default(),
         }
     }
 
@@ -50,12 +143,29 @@ impl TargetEnv {
     }
 
     /// Create an in-memory environment target from an existing map.
+    ///
+    /// Memory targets protect no keys by default.
     pub fn from_memory(map: BTreeMap<String, String>) -> Self {
         Self {
             kind: TargetEnvKind::Memory(map),
+            protected: ProtectedKeys::none(),
         }
     }
 
+    /// Replace the set of keys this target refuses to set.
+    pub fn protected_keys(mut self, protected: ProtectedKeys) -> Self {
+        self.protected = protected;
+        self
+    }
+
+    pub fn protected(&self) -> &ProtectedKeys {
+        &self.protected
+    }
+
+    pub(crate) fn set_protected_keys(&mut self, protected: ProtectedKeys) {
+        self.protected = protected;
+    }
+
     pub fn as_memory(&self) -> Option<&BTreeMap<String, String>> {
         match &self.kind {
             TargetEnvKind::Memory(map) => Some(map),
@@ -94,6 +204,12 @@ impl TargetEnv {
     }
 
     pub(crate) fn set_var(&mut self, key: &str, value: &str) -> std::io::Result<()> {
+        if self.protected.is_protected(key) {
+            return Err(IoError::new(
+                ErrorKind::PermissionDenied,
+                format!("refusing to set protected environment variable `{key}`"),
+            ));
+        }
         match &mut self.kind {
             TargetEnvKind::Process => {
                 validate_process_env_pair(key, value)?;
@@ -109,7 +225,7 @@ impl TargetEnv {
 }
 
 fn validate_process_env_pair(key: &str, value: &str) -> std::io::Result<()> {
-    if key.contains('\0') || key.contains('=') {
+    if key.is_empty() || key.contains('\0') || key.contains('=') {
         return Err(IoError::new(
             ErrorKind::InvalidInput,
             format!("invalid environment variable name `{key}`"),
@@ -6,6 +6,8 @@ pub enum Error {
     Io(std::io::Error),
     Parse(ParseError),
     InvalidEncoding(std::str::Utf8Error),
+    Validation(ValidationError),
+    Encryption(EncryptionError),
 }
 
 impl Display for Error {
@@ -14,6 +16,8 @@ impl Display for Error {
             Self::Io(err) => write!(f, "I/O error: {err}"),
             Self::Parse(err) => write!(f, "{err}"),
             Self::InvalidEncoding(err) => write!(f, "invalid UTF-8 input: {err}"),
+            Self::Validation(err) => write!(f, "{err}"),
+            Self::Encryption(err) => write!(f, "{err}"),
         }
     }
 }
@@ -24,6 +28,8 @@ impl StdError for Error {
             Self::Io(err) => Some(err),
             Self::Parse(err) => Some(err),
             Self::InvalidEncoding(err) => Some(err),
+            Self::Validation(err) => Some(err),
+            Self::Encryption(err) => Some(err),
         }
     }
 }
@@ -46,6 +52,18 @@ impl From<std::str::Utf8Error> for Error {
     }
 }
 
+impl From<ValidationError> for Error {
+    fn from(value: ValidationError) -> Self {
+        Self::Validation(value)
+    }
+}
+
+impl From<EncryptionError> for Error {
+    fn from(value: EncryptionError) -> Self {
+        Self::Encryption(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseError {
     pub line: u32,
@@ -77,6 +95,7 @@ pub enum ParseErrorKind {
     MissingKey,
     InvalidKey,
     UnterminatedQuote,
+    InvalidAnnotation,
 }
 
 impl Display for ParseErrorKind {
@@ -86,6 +105,95 @@ impl Display for ParseErrorKind {
             Self::MissingKey => write!(f, "missing key"),
             Self::InvalidKey => write!(f, "invalid key"),
             Self::UnterminatedQuote => write!(f, "unterminated quote"),
+            Self::InvalidAnnotation => write!(f, "invalid annotation"),
+        }
+    }
+}
+
+/// Every violation found while validating loaded variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub violations: Vec<Violation>,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} validation error{}",
+            self.violations.len(),
+            if self.violations.len() == 1 { "" } else { "s" }
+        )?;
+        for violation in &self.violations {
+            write!(f, "\n  {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl StdError for ValidationError {}
+
+/// A single invalid or missing variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub key: String,
+    pub kind: ViolationKind,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// A required variable is not set or is empty.
+    Missing,
+    /// The value does not match the declared type, e.g. `int` or `url`.
+    InvalidValue { expected: String },
+    /// A loaded key is not declared in the example file.
+    Undeclared,
+    /// The target refuses to set a protected key.
+    Protected,
+}
+
+impl Display for ViolationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "missing required variable"),
+            Self::InvalidValue { expected } => write!(f, "expected {expected}"),
+            Self::Undeclared => write!(f, "not declared in example file"),
+            Self::Protected => write!(f, "protected variable cannot be set"),
         }
     }
 }
+
+/// Failure to encrypt or decrypt a dotenv value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptionError {
+    /// Variable whose value failed, if any.
+    pub key: Option<String>,
+    pub message: String,
+}
+
+#[cfg(feature = "encryption")]
+impl EncryptionError {
+    pub(crate) fn new(key: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.map(str::to_owned),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for EncryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "encryption error for {key}: {}", self.message),
+            None => write!(f, "encryption error: {}", self.message),
+        }
+    }
+}
+
+impl StdError for EncryptionError {}
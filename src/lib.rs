@@ -7,17 +7,46 @@
 //! mutate the process environment and are `unsafe`, because callers must
 //! guarantee no concurrent process-environment access.
 
+#[cfg(feature = "encryption")]
+mod crypto;
+#[cfg(feature = "serde")]
+mod de;
 mod env;
 mod error;
 mod loader;
 mod model;
 mod parser;
+mod pattern;
+mod permissions;
+mod schema;
+mod secret;
+mod source;
+mod substitution;
+mod watch;
 
-pub use env::TargetEnv;
-pub use error::{Error, ParseError, ParseErrorKind};
-pub use loader::{EnvLoader, dotenv, from_filename, from_path, from_paths};
-pub use model::{Encoding, Entry, KeyParsingMode, LoadReport, LoadedEnv, SubstitutionMode};
+#[cfg(feature = "encryption")]
+pub use crypto::{
+    ENCRYPTED_PREFIX, EncryptReport, KEYS_FILE, KeyPair, decrypt_file, decrypt_value, encrypt_file,
+    encrypt_value,
+};
+#[cfg(feature = "serde")]
+pub use de::{DeserializeError, from_env, from_env_prefixed};
+pub use env::{ProtectedKeys, TargetEnv};
+pub use error::{
+    EncryptionError, Error, ParseError, ParseErrorKind, ValidationError, Violation, ViolationKind,
+};
+pub use loader::{Convention, EnvLoader, dotenv, from_filename, from_path, from_paths};
+pub use model::{
+    CheckLevel, Encoding, Entry, ExpansionPolicy, KeyCase, KeyParsingMode, LoadReport, LoadedEnv,
+    Origin, SearchBounds, SubstitutionMode,
+};
 pub use parser::{
     parse_bytes, parse_bytes_with_mode, parse_reader, parse_reader_with_mode, parse_str,
     parse_str_with_mode,
 };
+pub use pattern::KeyPattern;
+pub use schema::{Schema, SchemaVar, VarType};
+pub use secret::{SecretKeys, SecretString};
+pub use source::EnvSource;
+pub use substitution::{ReferenceProvider, ReferenceRegistry, Substitutor, VarLookup, expand};
+pub use watch::{EnvChange, EnvDiff, EnvWatcher, WatchHandle};
@@ -1,12 +1,27 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
-use std::io::{Error as IoError, ErrorKind};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{Error as IoError, ErrorKind, Read};
 use std::path::{Path, PathBuf};
-
-use crate::env::TargetEnv;
-use crate::error::Error;
-use crate::model::{Encoding, Entry, KeyParsingMode, LoadReport, LoadedEnv, SubstitutionMode};
-use crate::parser::parse_str_with_source;
+use std::sync::Arc;
+
+use crate::env::{ProtectedKeys, TargetEnv};
+use crate::error::{Error, ValidationError, Violation, ViolationKind};
+use crate::model::{
+    CheckLevel, Encoding, Entry, ExpansionPolicy, KeyCase, KeyParsingMode, LoadReport, LoadedEnv,
+    Origin, SearchBounds, SubstitutionMode,
+};
+use crate::parser::{Include, parse_str_with_includes, parse_str_with_source};
+use crate::pattern::{KeyPattern, glob_paths, is_path_glob};
+use crate::permissions::FileSecurity;
+use crate::schema::Schema;
+#[cfg(feature = "encryption")]
+use crate::secret::SecretString;
+use crate::secret::{SecretKeys, is_default_secret};
+use crate::source::{EnvSource, ReaderSource, StrSource};
+use crate::substitution::{
+    ExpandOptions, ReferenceProvider, ReferenceRegistry, SubstitutionResolver,
+};
 
 /// Load `.env` from the current working directory into the process environment.
 ///
@@ -57,19 +72,43 @@ pub unsafe fn from_filename(name: &str) -> Result<LoadReport, Error> {
     }
 }
 
+const DEFAULT_MAX_INCLUDE_DEPTH: usize = 8;
+
 /// Builder-style dotenv loader.
 ///
 /// `EnvLoader::new()` defaults to [`TargetEnv::memory`], which keeps values in
 /// an in-memory map and avoids process-global mutation by default.
 #[derive(Debug)]
 pub struct EnvLoader {
-    paths: Vec<PathBuf>,
+    sources: Vec<Source>,
+    convention: Convention,
     encoding: Encoding,
     required: bool,
     override_existing: bool,
     key_parsing_mode: KeyParsingMode,
     search_upward: bool,
+    cascade: bool,
+    search_bounds: SearchBounds,
     substitution_mode: SubstitutionMode,
+    references: ReferenceRegistry,
+    substitution_filters: bool,
+    expansion_policy: ExpansionPolicy,
+    schema: Option<Schema>,
+    example: Option<PathBuf>,
+    example_missing: CheckLevel,
+    example_extra: CheckLevel,
+    file_permissions: CheckLevel,
+    includes: bool,
+    max_include_depth: usize,
+    include_keys: Vec<KeyPattern>,
+    exclude_keys: Vec<KeyPattern>,
+    strip_prefix: Option<String>,
+    add_prefix: Option<String>,
+    key_case: KeyCase,
+    protected_keys: Option<ProtectedKeys>,
+    secret_keys: Option<SecretKeys>,
+    #[cfg(feature = "encryption")]
+    private_key: Option<SecretString>,
     verbose: bool,
     quiet: bool,
     target: TargetEnv,
@@ -83,8 +122,15 @@ impl EnvLoader {
         Self::default()
     }
 
+    /// Append a dotenv file.
+    ///
+    /// Paths containing `*`, `?`, or `{a,b}` are globs: every matching file
+    /// is loaded, alternatives in written order and the matches of each
+    /// sorted. Globs are resolved from the current directory without upward
+    /// search, and [`EnvLoader::required`] means at least one file must
+    /// match. Braces without a comma, as in `{app}.env`, are literal.
     pub fn path(mut self, path: impl AsRef<Path>) -> Self {
-        self.paths.push(path.as_ref().to_path_buf());
+        self.sources.push(Source::from_path(path.as_ref()));
         self
     }
 
@@ -93,11 +139,50 @@ impl EnvLoader {
         I: IntoIterator<Item = P>,
         P: AsRef<Path>,
     {
-        self.paths
-            .extend(paths.into_iter().map(|path| path.as_ref().to_path_buf()));
+        self.sources.extend(
+            paths
+                .into_iter()
+                .map(|path| Source::from_path(path.as_ref())),
+        );
         self
     }
 
+    /// Append every file with the `.env` extension in `dir`, in lexical order.
+    ///
+    /// Files merge like paths added with [`EnvLoader::paths`], so later
+    /// fragments win, and each counts toward [`LoadReport::files_read`]. A
+    /// missing directory follows [`EnvLoader::required`].
+    pub fn dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.sources.push(Source::Dir(dir.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Append a custom input, such as embedded or remote configuration.
+    ///
+    /// The source parses like a file but is never searched for upward or
+    /// checked for permissions, and `# @include` lines are treated as
+    /// comments.
+    pub fn source(mut self, source: impl EnvSource + 'static) -> Self {
+        self.sources.push(Source::Custom(Arc::new(source)));
+        self
+    }
+
+    /// Append in-memory dotenv text, such as defaults embedded with
+    /// `include_str!`. `name` is reported as the entries' source.
+    pub fn source_str(self, name: impl Into<String>, text: impl Into<Cow<'static, str>>) -> Self {
+        self.source(StrSource::new(name.into(), text.into()))
+    }
+
+    /// Append dotenv content from `reader`, read once at load time.
+    /// `name` is reported as the entries' source.
+    pub fn source_reader(
+        self,
+        name: impl Into<String>,
+        reader: impl std::io::Read + Send + 'static,
+    ) -> Self {
+        self.source(ReaderSource::new(name.into(), Box::new(reader)))
+    }
+
     /// Append paths using the common multi-environment dotenv convention.
     ///
     /// Precedence (highest to lowest):
@@ -110,7 +195,32 @@ impl EnvLoader {
     /// `dotenvor` merges files using "last file wins", so these paths are
     /// appended in reverse precedence order.
     pub fn convention(mut self, environment: impl AsRef<str>) -> Self {
-        self.paths.extend(convention_paths(environment.as_ref()));
+        self.sources.push(Source::Convention {
+            vars: Vec::new(),
+            default: environment.as_ref().to_owned(),
+        });
+        self
+    }
+
+    /// Append the [`EnvLoader::convention`] stack for an environment name read
+    /// from the first of `vars` that is set.
+    ///
+    /// Variables are looked up when loading, first in the target and then in
+    /// the process environment; when none is set, `default` is used.
+    pub fn convention_from_env(mut self, vars: &[&str], default: impl Into<String>) -> Self {
+        self.sources.push(Source::Convention {
+            vars: vars.iter().map(|var| (*var).to_owned()).collect(),
+            default: default.into(),
+        });
+        self
+    }
+
+    /// Replace the file stack used by [`EnvLoader::convention`] and
+    /// [`EnvLoader::convention_from_env`].
+    ///
+    /// Defaults to [`Convention::default`].
+    pub fn convention_template(mut self, convention: Convention) -> Self {
+        self.convention = convention;
         self
     }
 
@@ -146,11 +256,225 @@ impl EnvLoader {
         self
     }
 
+    /// Load every matching file from the outermost ancestor down to the
+    /// current directory instead of only the nearest one.
+    ///
+    /// Deeper files take precedence, so workspace-wide defaults can be
+    /// overridden per directory. Implies [`EnvLoader::search_upward`] and
+    /// honors [`EnvLoader::search_bounds`].
+    pub fn cascade(mut self, cascade: bool) -> Self {
+        self.cascade = cascade;
+        self
+    }
+
+    /// Limit how far [`EnvLoader::search_upward`] walks.
+    ///
+    /// Defaults to [`SearchBounds::default`], which stops only at the
+    /// filesystem root; see [`SearchBounds::project`] for a tighter preset.
+    pub fn search_bounds(mut self, search_bounds: SearchBounds) -> Self {
+        self.search_bounds = search_bounds;
+        self
+    }
+
     pub fn substitution_mode(mut self, substitution_mode: SubstitutionMode) -> Self {
         self.substitution_mode = substitution_mode;
         self
     }
 
+    /// Enable the built-in `${file:...}`, `${env:...}`, and `${base64:...}`
+    /// reference providers.
+    ///
+    /// Off by default: `file` and `env` let dotenv files read arbitrary files
+    /// and process variables, so only enable them for trusted files.
+    pub fn builtin_references(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.references.register_builtins();
+        } else {
+            self.references.unregister_builtins();
+        }
+        self
+    }
+
+    /// Register a provider for `${prefix:reference}` placeholders.
+    ///
+    /// Registering `file`, `env`, or `base64` replaces the built-in.
+    pub fn reference_provider(
+        mut self,
+        prefix: impl Into<String>,
+        provider: impl ReferenceProvider + 'static,
+    ) -> Self {
+        self.references.register(prefix, provider);
+        self
+    }
+
+    /// Replace the namespaced placeholder registry.
+    ///
+    /// The default is [`ReferenceRegistry::empty`].
+    pub fn reference_registry(mut self, references: ReferenceRegistry) -> Self {
+        self.references = references;
+        self
+    }
+
+    /// Enable `${NAME|filter|...}` pipelines in substitution.
+    ///
+    /// Supported filters are `upper`, `lower`, `trim`, `urlencode`, `base64`,
+    /// `json-escape`, and `default:VALUE`. Defaults to `false`, so values that
+    /// contain `|` inside braces are left untouched.
+    pub fn substitution_filters(mut self, substitution_filters: bool) -> Self {
+        self.substitution_filters = substitution_filters;
+        self
+    }
+
+    /// Choose which quoting styles are expanded and whether `$$` escapes `$`.
+    ///
+    /// Defaults to [`ExpansionPolicy::default`]; use
+    /// [`ExpansionPolicy::compose`] for files shared with Docker Compose.
+    pub fn expansion_policy(mut self, expansion_policy: ExpansionPolicy) -> Self {
+        self.expansion_policy = expansion_policy;
+        self
+    }
+
+    /// Validate the merged result against `schema` before applying it.
+    ///
+    /// Every violation is reported at once as [`Error::Validation`], and the
+    /// target is left untouched when validation fails. Declared defaults are
+    /// applied for keys that are set neither in files nor in the target.
+    pub fn schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Check the merged result against the keys listed in an example file.
+    ///
+    /// Keys declared in the example but set neither in the loaded files nor
+    /// in the target fail the load. Adjust this with
+    /// [`EnvLoader::example_missing`] and [`EnvLoader::example_extra`].
+    pub fn require_example(mut self, path: impl AsRef<Path>) -> Self {
+        self.example = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set how keys missing from the merged result are reported.
+    ///
+    /// Defaults to [`CheckLevel::Error`]. Only used with
+    /// [`EnvLoader::require_example`].
+    pub fn example_missing(mut self, level: CheckLevel) -> Self {
+        self.example_missing = level;
+        self
+    }
+
+    /// Set how loaded keys that the example file does not declare are reported.
+    ///
+    /// Defaults to [`CheckLevel::Ignore`]. Only used with
+    /// [`EnvLoader::require_example`].
+    pub fn example_extra(mut self, level: CheckLevel) -> Self {
+        self.example_extra = level;
+        self
+    }
+
+    /// Check dotenv files for unsafe permissions and ownership before use.
+    ///
+    /// On Unix, a file is reported when it is group- or world-writable, when
+    /// it holds secret values and is group- or world-readable, or when it or a
+    /// symlink naming it is owned by neither the current user nor root. The
+    /// opened file is checked before its content is used. This covers files
+    /// found by
+    /// [`EnvLoader::search_upward`]. With [`CheckLevel::Error`] the load fails
+    /// with a [`std::io::ErrorKind::PermissionDenied`] I/O error. Defaults to
+    /// [`CheckLevel::Ignore`]; other platforms are never checked.
+    pub fn file_permissions(mut self, level: CheckLevel) -> Self {
+        self.file_permissions = level;
+        self
+    }
+
+    /// Follow `# @include PATH` directives in dotenv files.
+    ///
+    /// Included files are resolved relative to the including file and merged
+    /// at the directive, so later lines override included values. Entries
+    /// keep the file they were read from as their source. Cycles and nesting
+    /// beyond [`EnvLoader::max_include_depth`] fail the load with an
+    /// [`std::io::ErrorKind::InvalidData`] I/O error; missing included files
+    /// follow [`EnvLoader::required`]. Defaults to `false`, which treats the
+    /// directive as a comment.
+    pub fn includes(mut self, includes: bool) -> Self {
+        self.includes = includes;
+        self
+    }
+
+    /// Limit how deeply include directives may nest.
+    ///
+    /// Defaults to 8.
+    pub fn max_include_depth(mut self, depth: usize) -> Self {
+        self.max_include_depth = depth;
+        self
+    }
+
+    /// Only load keys matching `pattern`.
+    ///
+    /// Repeat to accept keys matching any of several patterns. Patterns match
+    /// keys as written in the file, before any rewriting.
+    pub fn include_key(mut self, pattern: KeyPattern) -> Self {
+        self.include_keys.push(pattern);
+        self
+    }
+
+    /// Skip keys matching `pattern`, even when an include pattern accepts them.
+    pub fn exclude_key(mut self, pattern: KeyPattern) -> Self {
+        self.exclude_keys.push(pattern);
+        self
+    }
+
+    /// Remove `prefix` from keys that start with it after case normalization.
+    ///
+    /// Keys left empty, such as `BILLING_` itself, are skipped with a warning.
+    pub fn strip_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.strip_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Prepend `prefix` to every key after normalization and stripping.
+    pub fn add_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.add_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Normalize key case before prefixes are stripped or added.
+    pub fn key_case(mut self, key_case: KeyCase) -> Self {
+        self.key_case = key_case;
+        self
+    }
+
+    /// Replace the keys the target refuses to set.
+    ///
+    /// Process loads protect [`ProtectedKeys::default`] unless this is set.
+    /// Blocked keys are skipped and listed in [`LoadReport::blocked`], or fail
+    /// the load with [`Error::Validation`] when [`ProtectedKeys::strict`] is
+    /// enabled.
+    pub fn protected_keys(mut self, protected_keys: ProtectedKeys) -> Self {
+        self.protected_keys = Some(protected_keys);
+        self
+    }
+
+    /// Choose which keys hold secret values.
+    ///
+    /// Defaults to [`SecretKeys::default`]. Secret entries and
+    /// [`LoadedEnv::secrets`] are redacted in `Debug` output; schema variables
+    /// annotated `@secret` are always secret.
+    pub fn secret_keys(mut self, secret_keys: SecretKeys) -> Self {
+        self.secret_keys = Some(secret_keys);
+        self
+    }
+
+    /// Set the private key used to decrypt `encrypted:` values.
+    ///
+    /// Without one, each file's key is looked up in `DOTENV_PRIVATE_KEY_<ENV>`,
+    /// `DOTENV_PRIVATE_KEY`, and then `.env.keys` next to the file.
+    #[cfg(feature = "encryption")]
+    pub fn private_key(mut self, private_key: impl Into<SecretString>) -> Self {
+        self.private_key = Some(private_key.into());
+        self
+    }
+
     pub fn verbose(mut self, verbose: bool) -> Self {
         self.verbose = verbose;
         self
@@ -181,6 +505,10 @@ impl EnvLoader {
     pub fn parse_only(&self) -> Result<Vec<Entry>, Error> {
         let (mut entries, _) = self.collect_entries(true)?;
         self.apply_substitution(&mut entries);
+        self.rewrite_keys(&mut entries);
+        self.check_example(&entries)?;
+        self.apply_schema(&mut entries)?;
+        self.classify_secrets(&mut entries);
         self.log(&format!(
             "parsed {} entr{}",
             entries.len(),
@@ -191,6 +519,35 @@ impl EnvLoader {
 
     /// Safely load into memory and return the result in one call.
     pub fn load(mut self) -> Result<LoadedEnv, Error> {
+        self.load_snapshot()
+    }
+
+    /// Like [`EnvLoader::load`], but reads the inputs concurrently on Tokio's
+    /// blocking thread pool.
+    ///
+    /// Must be called within a Tokio runtime. Path resolution, `# @include`
+    /// files, permission checks, and custom sources all run off the async
+    /// worker threads. Entries merge in the same order and fail with the same
+    /// errors as [`EnvLoader::load`].
+    #[cfg(feature = "tokio")]
+    pub async fn load_async(self) -> Result<LoadedEnv, Error> {
+        self.check_memory_target()?;
+        let loader = Arc::new(self);
+        let collected = Self::collect_entries_async(&loader, true).await?;
+        let mut loader = Arc::try_unwrap(loader)
+            .unwrap_or_else(|_| unreachable!("every read finished before merging"));
+        loader.load_collected(collected)
+    }
+
+    /// Load into a copy of the in-memory target, leaving the loader ready to
+    /// load again.
+    pub(crate) fn load_snapshot(&mut self) -> Result<LoadedEnv, Error> {
+        self.check_memory_target()?;
+        let collected = self.collect_entries(true)?;
+        self.load_collected(collected)
+    }
+
+    fn check_memory_target(&self) -> Result<(), Error> {
         if self.target.as_memory().is_none() {
             return Err(IoError::new(
                 ErrorKind::InvalidInput,
@@ -198,13 +555,47 @@ impl EnvLoader {
             )
             .into());
         }
+        Ok(())
+    }
 
-        let report = self.load_into_target()?;
-        let env = self
+    fn load_collected(&mut self, collected: (Vec<Entry>, usize)) -> Result<LoadedEnv, Error> {
+        let base = self
             .target
-            .into_memory()
+            .as_memory()
+            .cloned()
             .expect("memory target validated before load");
-        Ok(LoadedEnv { report, env })
+        let mut tracked = Tracked::default();
+        let report = self.load_into_target(collected, Some(&mut tracked));
+        let env = std::mem::replace(
+            self.target
+                .as_memory_mut()
+                .expect("memory target validated before load"),
+            base,
+        );
+        let report = report?;
+        // Values kept from the initial map are classified by key alone.
+        let secret_keys = self.secret_keys.clone().unwrap_or_default();
+        tracked
+            .secrets
+            .extend(env.keys().filter(|key| secret_keys.is_secret(key)).cloned());
+        Ok(LoadedEnv {
+            report,
+            env,
+            origins: tracked.origins,
+            secrets: tracked.secrets,
+        })
+    }
+
+    /// Files the next load would read, in precedence order.
+    pub(crate) fn watched_paths(&self) -> Result<Vec<PathBuf>, Error> {
+        Ok(self
+            .effective_inputs()?
+            .into_iter()
+            .filter_map(|input| match input {
+                Input::File(path) => Some(path),
+                Input::Custom(_) => None,
+            })
+            .collect())
     }
 
     /// Load into the process environment.
@@ -215,16 +606,46 @@ impl EnvLoader {
     /// process environment while this function runs.
     pub unsafe fn load_and_modify(mut self) -> Result<LoadReport, Error> {
         self.target = unsafe { TargetEnv::process() };
-        self.load_into_target()
+        let collected = self.collect_entries(false)?;
+        self.load_into_target(collected, None)
     }
 
-    fn load_into_target(&mut self) -> Result<LoadReport, Error> {
-        let (mut entries, files_read) = self.collect_entries(false)?;
+    fn load_into_target(
+        &mut self,
+        (mut entries, files_read): (Vec<Entry>, usize),
+        mut tracked: Option<&mut Tracked>,
+    ) -> Result<LoadReport, Error> {
         self.apply_substitution(&mut entries);
+        self.rewrite_keys(&mut entries);
+        self.check_example(&entries)?;
+        self.apply_schema(&mut entries)?;
+        self.classify_secrets(&mut entries);
+        if let Some(protected_keys) = self.protected_keys.take() {
+            self.target.set_protected_keys(protected_keys);
+        }
+        let protected = self.target.protected();
+        let (blocked, entries): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|entry| protected.is_protected(&entry.key));
+        if protected.is_strict() && !blocked.is_empty() {
+            let violations = blocked
+                .into_iter()
+                .map(|entry| Violation {
+                    key: entry.key,
+                    kind: ViolationKind::Protected,
+                })
+                .collect();
+            return Err(ValidationError { violations }.into());
+        }
+
         let mut report = LoadReport {
             files_read,
             ..LoadReport::default()
         };
+        for entry in blocked {
+            self.warn(&format!("skipping protected key {}", entry.key));
+            report.blocked.push(entry.key);
+        }
 
         for entry in entries {
             if !self.override_existing && self.target.contains_key(&entry.key) {
@@ -236,6 +657,21 @@ impl EnvLoader {
             self.log(&format!("setting key {}", entry.key));
             self.target.set_var(&entry.key, &entry.value)?;
             report.loaded += 1;
+            let Some(tracked) = tracked.as_deref_mut() else {
+                continue;
+            };
+            if entry.secret {
+                tracked.secrets.insert(entry.key.clone());
+            }
+            if let Some(path) = entry.source {
+                tracked.origins.insert(
+                    entry.key,
+                    Origin {
+                        path,
+                        line: entry.line,
+                    },
+                );
+            }
         }
 
         self.log(&format!(
@@ -246,59 +682,241 @@ impl EnvLoader {
     }
 
     fn collect_entries(&self, include_source: bool) -> Result<(Vec<Entry>, usize), Error> {
-        let paths = self.effective_paths()?;
-        if paths.len() == 1 {
-            let path = &paths[0];
-            if let Some(parsed) = self.read_entries(path, include_source)? {
-                return Ok((parsed, 1));
-            }
-            return Ok((Vec::new(), 0));
-        }
-
-        let mut merged_entries = Vec::new();
-        let mut by_key = HashMap::<String, usize>::new();
-        let mut files_read = 0usize;
+        let inputs = self.effective_inputs()?;
+        merge_inputs(
+            inputs
+                .iter()
+                .map(|input| self.read_input(input, include_source)),
+        )
+    }
 
-        for path in paths {
-            let Some(parsed) = self.read_entries(&path, include_source)? else {
-                continue;
-            };
-            files_read += 1;
-            merged_entries.reserve(parsed.len());
-            by_key.reserve(parsed.len());
-
-            for entry in parsed {
-                if let Some(existing_idx) = by_key.get(&entry.key).copied() {
-                    merged_entries[existing_idx] = entry;
-                } else {
-                    by_key.insert(entry.key.clone(), merged_entries.len());
-                    merged_entries.push(entry);
+    /// [`EnvLoader::collect_entries`] with every input read started up front.
+    #[cfg(feature = "tokio")]
+    async fn collect_entries_async(
+        loader: &Arc<Self>,
+        include_source: bool,
+    ) -> Result<(Vec<Entry>, usize), Error> {
+        let resolver = Arc::clone(loader);
+        let inputs = join_blocking(tokio::task::spawn_blocking(move || {
+            resolver.effective_inputs()
+        }))
+        .await?;
+        let mut reads = inputs
+            .into_iter()
+            .map(|input| {
+                let loader = Arc::clone(loader);
+                tokio::task::spawn_blocking(move || loader.read_input(&input, include_source))
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let mut parsed = Vec::with_capacity(reads.len());
+        while let Some(read) = reads.next() {
+            match join_blocking(read).await {
+                Ok(entries) => parsed.push(Ok(entries)),
+                Err(err) => {
+                    reads.for_each(|read| read.abort());
+                    return Err(err);
                 }
             }
         }
+        merge_inputs(parsed.into_iter())
+    }
+
+    fn read_input(&self, input: &Input, include_source: bool) -> Result<Option<Vec<Entry>>, Error> {
+        match input {
+            Input::File(path) => self.read_entries(path, include_source),
+            Input::Custom(source) => self.read_custom(source.as_ref(), include_source),
+        }
+    }
 
-        Ok((merged_entries, files_read))
+    fn read_custom(
+        &self,
+        source: &dyn EnvSource,
+        include_source: bool,
+    ) -> Result<Option<Vec<Entry>>, Error> {
+        let name = source.name();
+        self.log(&format!("reading {name}"));
+        let bytes = match source.read() {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == ErrorKind::NotFound && !self.required => {
+                self.log(&format!("skipping missing {name}"));
+                return Ok(None);
+            }
+            Err(err) => return Err(IoError::new(err.kind(), format!("{name}: {err}")).into()),
+        };
+        let path = Path::new(name.as_ref());
+        #[cfg_attr(not(feature = "encryption"), allow(unused_mut))]
+        let (mut parsed, _) = self.parse_content(&bytes, path, include_source, false)?;
+        #[cfg(feature = "encryption")]
+        crate::crypto::decrypt_entries(
+            &mut parsed,
+            path,
+            self.private_key.as_ref(),
+            self.substitution_mode == SubstitutionMode::Expand,
+        )?;
+        Ok(Some(parsed))
     }
 
     fn read_entries(&self, path: &Path, include_source: bool) -> Result<Option<Vec<Entry>>, Error> {
+        self.read_entries_nested(path, include_source, &mut Vec::new())
+    }
+
+    /// Read `path`, splicing in included files; `chain` holds the canonical
+    /// paths of the files currently including it. Errors abort the whole
+    /// load, so `chain` is only restored on success.
+    fn read_entries_nested(
+        &self,
+        path: &Path,
+        include_source: bool,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<Option<Vec<Entry>>, Error> {
         self.log(&format!("reading {}", path.display()));
-        let bytes = match std::fs::read(path) {
-            Ok(bytes) => bytes,
-            Err(err) if err.kind() == std::io::ErrorKind::NotFound && !self.required => {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::NotFound && !self.required => {
                 self.log(&format!("skipping missing {}", path.display()));
                 return Ok(None);
             }
             Err(err) => return Err(err.into()),
         };
-        let content = decode(&bytes, self.encoding)?;
-        let parsed = parse_str_with_source(
-            content.as_ref(),
-            include_source.then_some(path),
-            self.key_parsing_mode,
+        let security = match self.file_permissions {
+            CheckLevel::Ignore => None,
+            _ => {
+                let security = FileSecurity::inspect(path, &file)?;
+                self.report_insecure_file(path, security.problems())?;
+                Some(security)
+            }
+        };
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        #[cfg_attr(not(feature = "encryption"), allow(unused_mut))]
+        let (mut parsed, includes) =
+            self.parse_content(&bytes, path, include_source, self.includes)?;
+        if let Some(security) = security
+            && parsed
+                .iter()
+                .any(|entry| self.is_secret_entry(entry) && !is_encrypted(&entry.value))
+        {
+            self.report_insecure_file(path, security.secret_problems())?;
+        }
+        #[cfg(feature = "encryption")]
+        crate::crypto::decrypt_entries(
+            &mut parsed,
+            path,
+            self.private_key.as_ref(),
             self.substitution_mode == SubstitutionMode::Expand,
+        )?;
+        if includes.is_empty() {
+            // Include-aware parsing keeps in-file duplicates for splicing.
+            return Ok(Some(dedupe_entries(parsed)));
+        }
+        self.splice_includes(path, parsed, includes, include_source, chain)
+            .map(Some)
+    }
+
+    fn parse_content(
+        &self,
+        bytes: &[u8],
+        path: &Path,
+        include_source: bool,
+        includes: bool,
+    ) -> Result<(Vec<Entry>, Vec<Include>), Error> {
+        let content = decode(bytes, self.encoding)?;
+        let source = include_source.then_some(path);
+        let expansion =
+            (self.substitution_mode == SubstitutionMode::Expand).then_some(self.expansion_policy);
+        if includes {
+            return Ok(parse_str_with_includes(
+                content.as_ref(),
+                source,
+                self.key_parsing_mode,
+                expansion,
+            )?);
+        }
+        let parsed =
+            parse_str_with_source(content.as_ref(), source, self.key_parsing_mode, expansion)?;
+        Ok((parsed, Vec::new()))
+    }
+
+    /// Merge included files at their directive positions; later entries win.
+    fn splice_includes(
+        &self,
+        path: &Path,
+        entries: Vec<Entry>,
+        includes: Vec<Include>,
+        include_source: bool,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<Vec<Entry>, Error> {
+        let include_error = |include: &Include, message: String| -> Error {
+            IoError::new(
+                ErrorKind::InvalidData,
+                format!("{}:{}: {message}", path.display(), include.line),
+            )
+            .into()
+        };
+        if chain.len() >= self.max_include_depth {
+            return Err(include_error(
+                &includes[0],
+                format!("includes nested deeper than {}", self.max_include_depth),
+            ));
+        }
+
+        chain.push(std::fs::canonicalize(path)?);
+        let base = path.parent().unwrap_or(Path::new(""));
+        let mut merged = Vec::with_capacity(entries.len());
+        let mut by_key = HashMap::<String, usize>::new();
+        let mut entries = entries.into_iter();
+        let mut consumed = 0;
+        for include in &includes {
+            for entry in entries.by_ref().take(include.index - consumed) {
+                merge_entry(&mut merged, &mut by_key, entry);
+            }
+            consumed = include.index;
+
+            let target = base.join(&include.path);
+            if let Ok(canonical) = std::fs::canonicalize(&target)
+                && chain.contains(&canonical)
+            {
+                return Err(include_error(
+                    include,
+                    format!("include cycle through {}", target.display()),
+                ));
+            }
+            self.log(&format!("including {}", target.display()));
+            let included = self.read_entries_nested(&target, include_source, chain)?;
+            for entry in included.into_iter().flatten() {
+                merge_entry(&mut merged, &mut by_key, entry);
+            }
+        }
+        for entry in entries {
+            merge_entry(&mut merged, &mut by_key, entry);
+        }
+        chain.pop();
+        Ok(merged)
+    }
+
+    /// Warn about or refuse `path` according to
+    /// [`EnvLoader::file_permissions`].
+    fn report_insecure_file(&self, path: &Path, problems: Vec<String>) -> Result<(), Error> {
+        if problems.is_empty() {
+            return Ok(());
+        }
+        if self.file_permissions == CheckLevel::Warn {
+            for problem in &problems {
+                self.warn(&format!("{} {problem}", path.display()));
+            }
+            return Ok(());
+        }
+        Err(IoError::new(
+            ErrorKind::PermissionDenied,
+            format!(
+                "refusing to read {}: it {}",
+                path.display(),
+                problems.join("; it ")
+            ),
         )
-        .map_err(Error::from)?;
-        Ok(Some(parsed))
+        .into())
     }
 
     fn apply_substitution(&self, entries: &mut [Entry]) {
@@ -310,26 +928,283 @@ impl EnvLoader {
             entries,
             &self.target,
             self.override_existing,
-            self.key_parsing_mode,
+            ExpandOptions {
+                key_parsing_mode: self.key_parsing_mode,
+                references: &self.references,
+                filters: self.substitution_filters,
+                dollar_escape: self.expansion_policy.dollar_escape,
+            },
         );
         for entry in entries.iter_mut() {
             entry.value = resolver.resolve_entry(&entry.key);
         }
     }
 
-    fn effective_paths(&self) -> Result<Vec<PathBuf>, Error> {
-        let requested_paths = if self.paths.is_empty() {
-            vec![PathBuf::from(".env")]
+    /// Apply key filters and rewrites, keeping the last entry when rewritten
+    /// keys collide.
+    fn rewrite_keys(&self, entries: &mut Vec<Entry>) {
+        if self.include_keys.is_empty()
+            && self.exclude_keys.is_empty()
+            && self.strip_prefix.is_none()
+            && self.add_prefix.is_none()
+            && self.key_case == KeyCase::Preserve
+        {
+            return;
+        }
+
+        let mut rewritten = Vec::with_capacity(entries.len());
+        let mut by_key = HashMap::<String, usize>::new();
+        for mut entry in entries.drain(..) {
+            let included = self.include_keys.is_empty()
+                || self
+                    .include_keys
+                    .iter()
+                    .any(|pattern| pattern.matches(&entry.key));
+            if !included
+                || self
+                    .exclude_keys
+                    .iter()
+                    .any(|pattern| pattern.matches(&entry.key))
+            {
+                self.log(&format!("filtering out key {}", entry.key));
+                continue;
+            }
+
+            let mut key = match self.key_case {
+                KeyCase::Preserve => entry.key.clone(),
+                KeyCase::Upper => entry.key.to_ascii_uppercase(),
+                KeyCase::Lower => entry.key.to_ascii_lowercase(),
+            };
+            if let Some(prefix) = &self.strip_prefix
+                && key.starts_with(prefix.as_str())
+            {
+                key.drain(..prefix.len());
+            }
+            if let Some(prefix) = &self.add_prefix {
+                key.insert_str(0, prefix);
+            }
+            if key.is_empty() {
+                self.warn(&format!(
+                    "skipping key {}: empty after rewriting",
+                    entry.key
+                ));
+                continue;
+            }
+            if key != entry.key {
+                self.log(&format!("rewriting key {} to {key}", entry.key));
+                entry.key = key;
+            }
+
+            if let Some(existing_idx) = by_key.get(&entry.key).copied() {
+                rewritten[existing_idx] = entry;
+            } else {
+                by_key.insert(entry.key.clone(), rewritten.len());
+                rewritten.push(entry);
+            }
+        }
+        *entries = rewritten;
+    }
+
+    fn check_example(&self, entries: &[Entry]) -> Result<(), Error> {
+        let Some(example) = &self.example else {
+            return Ok(());
+        };
+
+        self.log(&format!("reading example {}", example.display()));
+        let bytes = std::fs::read(example)?;
+        let declared = parse_str_with_source(
+            decode(&bytes, self.encoding)?.as_ref(),
+            None,
+            self.key_parsing_mode,
+            None,
+        )?;
+
+        let mut violations = Vec::new();
+        let mut report = |key: &str, kind: ViolationKind, level: CheckLevel| match level {
+            CheckLevel::Ignore => {}
+            CheckLevel::Warn => self.warn(&format!("{key}: {kind}")),
+            CheckLevel::Error => violations.push(Violation {
+                key: key.to_owned(),
+                kind,
+            }),
+        };
+
+        for declared_entry in &declared {
+            let key = declared_entry.key.as_str();
+            if !entries.iter().any(|entry| entry.key == key) && !self.target.contains_key(key) {
+                report(key, ViolationKind::Missing, self.example_missing);
+            }
+        }
+        for entry in entries {
+            if !declared.iter().any(|declared| declared.key == entry.key) {
+                report(&entry.key, ViolationKind::Undeclared, self.example_extra);
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { violations }.into())
+        }
+    }
+
+    fn apply_schema(&self, entries: &mut Vec<Entry>) -> Result<(), Error> {
+        let Some(schema) = &self.schema else {
+            return Ok(());
+        };
+
+        let pending = entries
+            .iter()
+            .filter(|entry| self.override_existing || !self.target.contains_key(&entry.key))
+            .map(|entry| (entry.key.as_str(), entry.value.as_str()))
+            .collect::<HashMap<_, _>>();
+        let merged = |key: &str| {
+            pending
+                .get(key)
+                .map(|value| (*value).to_owned())
+                .or_else(|| self.target.get_var(key))
+        };
+
+        let defaults = schema.check(&merged)?;
+        for (key, value) in defaults {
+            self.log(&format!("applying schema default for {key}"));
+            entries.push(Entry {
+                key,
+                value,
+                source: None,
+                line: 0,
+                secret: false,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reclassify entries with the configured [`SecretKeys`] and mark keys
+    /// the schema declares secret.
+    fn classify_secrets(&self, entries: &mut [Entry]) {
+        for entry in entries.iter_mut() {
+            entry.secret = self.is_secret_entry(entry);
+        }
+    }
+
+    fn is_secret_entry(&self, entry: &Entry) -> bool {
+        let secret = match &self.secret_keys {
+            Some(secret_keys) => secret_keys.is_secret(&entry.key),
+            // Keep flags from before keys were rewritten.
+            None => entry.secret || is_default_secret(&entry.key),
+        };
+        secret
+            || self
+                .schema
+                .as_ref()
+                .and_then(|schema| schema.get(&entry.key))
+                .is_some_and(|var| var.secret)
+    }
+
+    fn effective_inputs(&self) -> Result<Vec<Input>, Error> {
+        let default_sources = [Source::File(PathBuf::from(".env"))];
+        let sources = if self.sources.is_empty() {
+            &default_sources[..]
         } else {
-            self.paths.clone()
+            &self.sources
+        };
+        let start_dir = if self.search_upward || self.cascade {
+            Some(std::env::current_dir()?)
+        } else {
+            None
         };
 
-        if !self.search_upward {
-            return Ok(requested_paths);
+        let resolve_file = |path: &Path| match &start_dir {
+            Some(start_dir) => {
+                resolve_upward_paths(start_dir, path, &self.search_bounds, self.cascade)
+            }
+            None => vec![path.to_path_buf()],
+        };
+
+        let mut inputs = Vec::with_capacity(sources.len());
+        for source in sources {
+            let paths = match source {
+                Source::File(path) => resolve_file(path),
+                Source::Dir(dir) => self.dir_paths(dir)?,
+                Source::Glob(pattern) => self.glob_paths(pattern)?,
+                Source::Convention { vars, default } => {
+                    let environment = self.detect_environment(vars);
+                    let environment = environment.as_deref().unwrap_or(default);
+                    self.log(&format!("using environment `{environment}`"));
+                    let mut paths = self
+                        .convention
+                        .paths(environment)
+                        .iter()
+                        .flat_map(|path| resolve_file(path))
+                        .collect::<Vec<_>>();
+                    if self.convention.optional {
+                        paths.retain(|path| {
+                            let exists = path.exists();
+                            if !exists {
+                                self.log(&format!("skipping missing {}", path.display()));
+                            }
+                            exists
+                        });
+                    }
+                    paths
+                }
+                Source::Custom(source) => {
+                    inputs.push(Input::Custom(Arc::clone(source)));
+                    continue;
+                }
+            };
+            inputs.extend(paths.into_iter().map(Input::File));
+        }
+        Ok(inputs)
+    }
+
+    /// The first non-empty value of `vars`, read from the target and then
+    /// the process environment.
+    fn detect_environment(&self, vars: &[String]) -> Option<String> {
+        vars.iter().find_map(|var| {
+            self.target
+                .get_var(var)
+                .or_else(|| std::env::var(var).ok())
+                .map(|value| value.trim().to_owned())
+                .filter(|value| !value.is_empty())
+        })
+    }
+
+    fn glob_paths(&self, pattern: &str) -> Result<Vec<PathBuf>, Error> {
+        let paths = glob_paths(pattern)?;
+        if paths.is_empty() {
+            if self.required {
+                return Err(IoError::new(
+                    ErrorKind::NotFound,
+                    format!("no files match `{pattern}`"),
+                )
+                .into());
+            }
+            self.log(&format!("no files match {pattern}"));
         }
+        Ok(paths)
+    }
 
-        let start_dir = std::env::current_dir()?;
-        Ok(resolve_paths_upward_from(&start_dir, &requested_paths))
+    fn dir_paths(&self, dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        self.log(&format!("listing {}", dir.display()));
+        let read_dir = match std::fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound && !self.required => {
+                self.log(&format!("skipping missing {}", dir.display()));
+                return Ok(Vec::new());
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut paths = Vec::new();
+        for entry in read_dir {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "env") && path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
     }
 
     fn logging_enabled(&self) -> bool {
@@ -341,18 +1216,86 @@ impl EnvLoader {
             eprintln!("dotenvor: {message}");
         }
     }
+
+    fn warn(&self, message: &str) {
+        if !self.quiet {
+            eprintln!("dotenvor: warning: {message}");
+        }
+    }
+}
+
+/// A configured input, expanded into files at load time.
+#[derive(Debug, Clone)]
+enum Source {
+    File(PathBuf),
+    /// Every `*.env` file in the directory.
+    Dir(PathBuf),
+    /// Every file matching a path glob.
+    Glob(String),
+    /// The convention stack for the environment named by the first set
+    /// variable in `vars`, or `default`.
+    Convention {
+        vars: Vec<String>,
+        default: String,
+    },
+    Custom(Arc<dyn EnvSource>),
+}
+
+/// A single file or custom source to read, in precedence order.
+#[derive(Debug)]
+enum Input {
+    File(PathBuf),
+    Custom(Arc<dyn EnvSource>),
+}
+
+impl Source {
+    fn from_path(path: &Path) -> Self {
+        match path.to_str() {
+            Some(pattern) if is_path_glob(pattern) => Self::Glob(pattern.to_owned()),
+            _ => Self::File(path.to_path_buf()),
+        }
+    }
+}
+
+/// Provenance collected for [`EnvLoader::load`].
+#[derive(Default)]
+struct Tracked {
+    origins: BTreeMap<String, Origin>,
+    secrets: BTreeSet<String>,
 }
 
 impl Default for EnvLoader {
     fn default() -> Self {
         Self {
-            paths: Vec::new(),
+            sources: Vec::new(),
+            convention: Convention::default(),
             encoding: Encoding::Utf8,
             required: true,
             override_existing: false,
             key_parsing_mode: KeyParsingMode::Strict,
             search_upward: false,
+            cascade: false,
+            search_bounds: SearchBounds::default(),
             substitution_mode: SubstitutionMode::Disabled,
+            references: ReferenceRegistry::empty(),
+            substitution_filters: false,
+            expansion_policy: ExpansionPolicy::default(),
+            schema: None,
+            example: None,
+            example_missing: CheckLevel::Error,
+            example_extra: CheckLevel::Ignore,
+            file_permissions: CheckLevel::Ignore,
+            includes: false,
+            max_include_depth: DEFAULT_MAX_INCLUDE_DEPTH,
+            include_keys: Vec::new(),
+            exclude_keys: Vec::new(),
+            strip_prefix: None,
+            add_prefix: None,
+            key_case: KeyCase::Preserve,
+            protected_keys: None,
+            secret_keys: None,
+            #[cfg(feature = "encryption")]
+            private_key: None,
             verbose: false,
             quiet: false,
             target: TargetEnv::memory(),
@@ -375,297 +1318,240 @@ fn decode_latin1(bytes: &[u8]) -> String {
     output
 }
 
-fn convention_paths(environment: &str) -> Vec<PathBuf> {
-    let environment = environment.trim();
-    let mut paths = Vec::with_capacity(4);
-
-    push_unique_path(&mut paths, PathBuf::from(".env"));
-    if !environment.is_empty() {
-        push_unique_path(&mut paths, PathBuf::from(format!(".env.{environment}")));
-    }
-    push_unique_path(&mut paths, PathBuf::from(".env.local"));
-    if !environment.is_empty() {
-        push_unique_path(
-            &mut paths,
-            PathBuf::from(format!(".env.{environment}.local")),
-        );
-    }
-
-    paths
-}
-
-fn push_unique_path(paths: &mut Vec<PathBuf>, path: PathBuf) {
-    if !paths.iter().any(|existing| existing == &path) {
-        paths.push(path);
-    }
+/// File stack loaded by [`EnvLoader::convention`].
+///
+/// Templates are expanded in order, with `{env}` replaced by the environment
+/// name; templates mentioning `{env}` are skipped when the name is blank, and
+/// repeated paths are loaded once. Later files take precedence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Convention {
+    templates: Vec<(String, EnvironmentFilter)>,
+    optional: bool,
 }
 
-fn resolve_paths_upward_from(start_dir: &Path, requested_paths: &[PathBuf]) -> Vec<PathBuf> {
-    requested_paths
-        .iter()
-        .map(|requested| resolve_upward_path(start_dir, requested))
-        .collect()
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EnvironmentFilter {
+    Any,
+    Only(Vec<String>),
+    Except(Vec<String>),
 }
 
-fn resolve_upward_path(start_dir: &Path, requested: &Path) -> PathBuf {
-    if requested.is_absolute() {
-        return requested.to_path_buf();
-    }
-
-    let fallback = start_dir.join(requested);
-    let mut current = Some(start_dir);
-    while let Some(dir) = current {
-        let candidate = dir.join(requested);
-        if candidate.is_file() {
-            return candidate;
+impl EnvironmentFilter {
+    fn allows(&self, environment: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Only(environments) => environments.iter().any(|name| name == environment),
+            Self::Except(environments) => !environments.iter().any(|name| name == environment),
         }
-        current = dir.parent();
     }
-
-    fallback
 }
 
-struct SubstitutionResolver<'a> {
-    raw_values: HashMap<String, String>,
-    resolved_values: HashMap<String, String>,
-    target: &'a TargetEnv,
-    override_existing: bool,
-    key_parsing_mode: KeyParsingMode,
+impl Default for Convention {
+    /// `.env`, `.env.{env}`, `.env.local`, then `.env.{env}.local`.
+    fn default() -> Self {
+        Self::new()
+            .file(".env")
+            .file(".env.{env}")
+            .file(".env.local")
+            .file(".env.{env}.local")
+    }
 }
 
-impl<'a> SubstitutionResolver<'a> {
-    fn new(
-        entries: &[Entry],
-        target: &'a TargetEnv,
-        override_existing: bool,
-        key_parsing_mode: KeyParsingMode,
-    ) -> Self {
-        let raw_values = entries
-            .iter()
-            .map(|entry| (entry.key.clone(), entry.value.clone()))
-            .collect();
-
+impl Convention {
+    /// An empty stack.
+    pub fn new() -> Self {
         Self {
-            raw_values,
-            resolved_values: HashMap::new(),
-            target,
-            override_existing,
-            key_parsing_mode,
+            templates: Vec::new(),
+            optional: false,
         }
     }
 
-    fn resolve_entry(&mut self, key: &str) -> String {
-        self.resolve_key(key, &mut Vec::new())
+    /// Skip missing files in this stack even when [`EnvLoader::required`] is
+    /// set. Defaults to `false`.
+    pub fn optional(mut self, optional: bool) -> Self {
+        self.optional = optional;
+        self
     }
 
-    fn resolve_key(&mut self, key: &str, stack: &mut Vec<String>) -> String {
-        if let Some(existing) = self.resolved_values.get(key) {
-            return existing.clone();
-        }
-
-        if !self.override_existing && self.target.contains_key(key) {
-            let existing = self.target.get_var(key).unwrap_or_default();
-            self.resolved_values
-                .insert(key.to_owned(), existing.clone());
-            return existing;
-        }
-
-        let Some(raw_value) = self.raw_values.get(key).cloned() else {
-            return self.target.get_var(key).unwrap_or_default();
-        };
-
-        stack.push(key.to_owned());
-        let expanded =
-            expand_template(&raw_value, self.key_parsing_mode, |name, token, default| {
-                self.resolve_placeholder(name, token, default, stack)
-            });
-        stack.pop();
-
-        self.resolved_values
-            .insert(key.to_owned(), expanded.clone());
-        expanded
+    /// Add `template` for every environment.
+    pub fn file(mut self, template: impl Into<String>) -> Self {
+        self.templates
+            .push((template.into(), EnvironmentFilter::Any));
+        self
     }
 
-    fn resolve_placeholder(
-        &mut self,
-        name: &str,
-        token: &str,
-        default: Option<&str>,
-        stack: &mut Vec<String>,
-    ) -> String {
-        if stack.iter().any(|item| item == name) {
-            return default.unwrap_or(token).to_owned();
-        }
+    /// Add `template` only for the listed environments.
+    pub fn file_if(mut self, template: impl Into<String>, environments: &[&str]) -> Self {
+        self.templates.push((
+            template.into(),
+            EnvironmentFilter::Only(environments.iter().map(|name| (*name).to_owned()).collect()),
+        ));
+        self
+    }
 
-        let resolved = if self.raw_values.contains_key(name) {
-            Some(self.resolve_key(name, stack))
-        } else {
-            self.target.get_var(name)
-        };
+    /// Add `template` except for the listed environments, e.g. to skip
+    /// `.env.local` in `test`.
+    pub fn file_unless(mut self, template: impl Into<String>, environments: &[&str]) -> Self {
+        self.templates.push((
+            template.into(),
+            EnvironmentFilter::Except(environments.iter().map(|name| (*name).to_owned()).collect()),
+        ));
+        self
+    }
 
-        if let Some(value) = resolved {
-            if default.is_some() && value.is_empty() {
-                return default.unwrap_or_default().to_owned();
+    /// Expand the stack for `environment`, lowest precedence first.
+    pub fn paths(&self, environment: &str) -> Vec<PathBuf> {
+        let environment = environment.trim();
+        let mut paths = Vec::with_capacity(self.templates.len());
+        for (template, filter) in &self.templates {
+            let uses_environment = template.contains("{env}");
+            if (uses_environment && environment.is_empty()) || !filter.allows(environment) {
+                continue;
             }
-            return value;
+            push_unique_path(
+                &mut paths,
+                PathBuf::from(template.replace("{env}", environment)),
+            );
         }
+        paths
+    }
+}
 
-        default.unwrap_or(token).to_owned()
+/// Await a blocking task, re-raising its panic.
+#[cfg(feature = "tokio")]
+async fn join_blocking<T>(task: tokio::task::JoinHandle<Result<T, Error>>) -> Result<T, Error> {
+    match task.await {
+        Ok(result) => result,
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => Err(IoError::other(err).into()),
     }
 }
 
-fn expand_template<F>(input: &str, key_parsing_mode: KeyParsingMode, mut resolve: F) -> String
-where
-    F: FnMut(&str, &str, Option<&str>) -> String,
-{
-    let mut out = String::with_capacity(input.len());
-    let mut cursor = 0usize;
-    let mut idx = 0usize;
-    let bytes = input.as_bytes();
-
-    while idx < bytes.len() {
-        if bytes[idx] != b'$' {
-            idx += 1;
-            continue;
+/// Merge parsed inputs in order, stopping at the first error.
+fn merge_inputs(
+    mut parsed: impl ExactSizeIterator<Item = Result<Option<Vec<Entry>>, Error>>,
+) -> Result<(Vec<Entry>, usize), Error> {
+    if parsed.len() == 1 {
+        if let Some(entries) = parsed.next().expect("one input")? {
+            return Ok((entries, 1));
         }
+        return Ok((Vec::new(), 0));
+    }
 
-        if idx > 0 && bytes[idx - 1] == b'\\' {
-            out.push_str(&input[cursor..idx - 1]);
-            out.push('$');
-            cursor = idx + 1;
-            idx += 1;
-            continue;
-        }
+    let mut merged_entries = Vec::new();
+    let mut by_key = HashMap::<String, usize>::new();
+    let mut files_read = 0usize;
 
-        let Some(placeholder) = parse_placeholder(input, idx, key_parsing_mode) else {
-            idx += 1;
+    for entries in parsed {
+        let Some(entries) = entries? else {
             continue;
         };
+        files_read += 1;
+        merged_entries.reserve(entries.len());
+        by_key.reserve(entries.len());
 
-        let name = &input[placeholder.name_start..placeholder.name_end];
-        let token = &input[idx..placeholder.token_end];
-        let default = placeholder.default.map(|(start, end)| &input[start..end]);
-
-        out.push_str(&input[cursor..idx]);
-        out.push_str(&resolve(name, token, default));
-
-        cursor = placeholder.token_end;
-        idx = placeholder.token_end;
+        for entry in entries {
+            merge_entry(&mut merged_entries, &mut by_key, entry);
+        }
     }
 
-    out.push_str(&input[cursor..]);
-    out
+    Ok((merged_entries, files_read))
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Placeholder {
-    name_start: usize,
-    name_end: usize,
-    default: Option<(usize, usize)>,
-    token_end: usize,
+/// Keep the last entry for each key, at the position of its first.
+fn dedupe_entries(entries: Vec<Entry>) -> Vec<Entry> {
+    let mut merged = Vec::with_capacity(entries.len());
+    let mut by_key = HashMap::<String, usize>::new();
+    for entry in entries {
+        merge_entry(&mut merged, &mut by_key, entry);
+    }
+    merged
 }
 
-fn parse_placeholder(
-    input: &str,
-    start: usize,
-    key_parsing_mode: KeyParsingMode,
-) -> Option<Placeholder> {
-    let bytes = input.as_bytes();
-    if start + 1 >= bytes.len() {
-        return None;
+/// Add `entry`, replacing an earlier entry with the same key in place.
+fn merge_entry(entries: &mut Vec<Entry>, by_key: &mut HashMap<String, usize>, entry: Entry) {
+    if let Some(existing_idx) = by_key.get(&entry.key).copied() {
+        entries[existing_idx] = entry;
+    } else {
+        by_key.insert(entry.key.clone(), entries.len());
+        entries.push(entry);
     }
+}
 
-    if bytes[start + 1] == b'{' {
-        let mut end = start + 2;
-        while end < bytes.len() && bytes[end] != b'}' {
-            end += 1;
-        }
-
-        if end >= bytes.len() {
-            return None;
-        }
-
-        let name_start = start + 2;
-        let token_end = end + 1;
-
-        if key_parsing_mode == KeyParsingMode::Strict {
-            let inner = &input[name_start..end];
-            if let Some(operator_idx) = inner.find(":-") {
-                let name_end = name_start + operator_idx;
-                let default_start = name_end + 2;
-                let name = &input[name_start..name_end];
-                if name.is_empty()
-                    || !name
-                        .bytes()
-                        .all(|byte| is_braced_var_char(byte, key_parsing_mode))
-                {
-                    return None;
-                }
-
-                return Some(Placeholder {
-                    name_start,
-                    name_end,
-                    default: Some((default_start, end)),
-                    token_end,
-                });
-            }
-        }
-
-        let name_end = end;
-        let name = &input[name_start..name_end];
-        if name.is_empty()
-            || !name
-                .bytes()
-                .all(|byte| is_braced_var_char(byte, key_parsing_mode))
-        {
-            return None;
-        }
-
-        return Some(Placeholder {
-            name_start,
-            name_end,
-            default: None,
-            token_end,
-        });
+fn push_unique_path(paths: &mut Vec<PathBuf>, path: PathBuf) {
+    if !paths.iter().any(|existing| existing == &path) {
+        paths.push(path);
     }
+}
 
-    let name_start = start + 1;
-    if !is_unbraced_var_start(bytes[name_start]) {
-        return None;
+fn resolve_upward_paths(
+    start_dir: &Path,
+    requested: &Path,
+    bounds: &SearchBounds,
+    cascade: bool,
+) -> Vec<PathBuf> {
+    if cascade {
+        cascade_upward_paths(start_dir, requested, bounds)
+    } else {
+        vec![resolve_upward_path(start_dir, requested, bounds)]
     }
+}
 
-    let mut name_end = name_start + 1;
-    while name_end < bytes.len() && is_unbraced_var_char(bytes[name_end]) {
-        name_end += 1;
+fn resolve_upward_path(start_dir: &Path, requested: &Path, bounds: &SearchBounds) -> PathBuf {
+    if requested.is_absolute() {
+        return requested.to_path_buf();
     }
 
-    Some(Placeholder {
-        name_start,
-        name_end,
-        default: None,
-        token_end: name_end,
-    })
+    upward_candidates(start_dir, requested, bounds)
+        .next()
+        .unwrap_or_else(|| start_dir.join(requested))
 }
 
-fn is_braced_var_char(byte: u8, key_parsing_mode: KeyParsingMode) -> bool {
-    match key_parsing_mode {
-        KeyParsingMode::Strict => {
-            byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'.' || byte == b'-'
-        }
-        KeyParsingMode::Permissive => is_valid_permissive_key_byte(byte),
+/// Every existing `requested` file within `bounds`, outermost first.
+fn cascade_upward_paths(start_dir: &Path, requested: &Path, bounds: &SearchBounds) -> Vec<PathBuf> {
+    if requested.is_absolute() {
+        return vec![requested.to_path_buf()];
     }
+
+    let mut found = upward_candidates(start_dir, requested, bounds).collect::<Vec<_>>();
+    if found.is_empty() {
+        found.push(start_dir.join(requested));
+    }
+    found.reverse();
+    found
 }
 
-fn is_valid_permissive_key_byte(byte: u8) -> bool {
-    byte.is_ascii() && (b'!'..=b'~').contains(&byte) && byte != b'='
+/// Existing `requested` files from `start_dir` upward, nearest first.
+fn upward_candidates<'a>(
+    start_dir: &'a Path,
+    requested: &'a Path,
+    bounds: &'a SearchBounds,
+) -> impl Iterator<Item = PathBuf> + 'a {
+    let mut current = Some(start_dir);
+    let mut depth = 0;
+    std::iter::from_fn(move || {
+        let dir = current?;
+        current = if bounds.stops_at(dir, depth) {
+            None
+        } else {
+            dir.parent()
+                .filter(|parent| !bounds.blocks_crossing(dir, parent))
+        };
+        depth += 1;
+        Some(dir.join(requested))
+    })
+    .filter(|candidate| candidate.is_file())
 }
 
-fn is_unbraced_var_start(byte: u8) -> bool {
-    byte.is_ascii_alphabetic() || byte == b'_'
+/// Whether `value` is still encrypted and therefore safe to expose.
+#[cfg(feature = "encryption")]
+fn is_encrypted(value: &str) -> bool {
+    value.starts_with(crate::crypto::ENCRYPTED_PREFIX)
 }
 
-fn is_unbraced_var_char(byte: u8) -> bool {
-    byte.is_ascii_alphanumeric() || byte == b'_'
+#[cfg(not(feature = "encryption"))]
+fn is_encrypted(_value: &str) -> bool {
+    false
 }
 
 fn plural<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
@@ -674,8 +1560,8 @@ fn plural<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
 
 #[cfg(test)]
 mod tests {
-    use super::{EnvLoader, convention_paths, resolve_upward_path};
-    use crate::model::KeyParsingMode;
+    use super::{Convention, EnvLoader, cascade_upward_paths, resolve_upward_path};
+    use crate::model::{KeyParsingMode, SearchBounds};
     use std::path::Path;
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -723,7 +1609,7 @@ mod tests {
     fn convention_builder_sets_common_stack_paths() {
         let loader = EnvLoader::new().convention("development");
         assert_eq!(
-            loader.paths,
+            effective_paths(&loader),
             vec![
                 PathBuf::from(".env"),
                 PathBuf::from(".env.development"),
@@ -737,7 +1623,7 @@ mod tests {
     fn convention_builder_handles_blank_environment_name() {
         let loader = EnvLoader::new().convention("   ");
         assert_eq!(
-            loader.paths,
+            effective_paths(&loader),
             vec![PathBuf::from(".env"), PathBuf::from(".env.local")]
         );
     }
@@ -745,7 +1631,7 @@ mod tests {
     #[test]
     fn convention_paths_avoid_duplicates() {
         assert_eq!(
-            convention_paths("local"),
+            Convention::default().paths("local"),
             vec![
                 PathBuf::from(".env"),
                 PathBuf::from(".env.local"),
@@ -754,6 +1640,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn convention_templates_apply_conditions() {
+        let convention = Convention::new()
+            .file("config/default.env")
+            .file("config/{env}.env")
+            .file_unless(".env.local", &["test"])
+            .file_if("config/debug.env", &["development"]);
+
+        assert_eq!(
+            convention.paths("test"),
+            vec![
+                PathBuf::from("config/default.env"),
+                PathBuf::from("config/test.env"),
+            ]
+        );
+        assert_eq!(
+            convention.paths(" development "),
+            vec![
+                PathBuf::from("config/default.env"),
+                PathBuf::from("config/development.env"),
+                PathBuf::from(".env.local"),
+                PathBuf::from("config/debug.env"),
+            ]
+        );
+        assert_eq!(
+            convention.paths(""),
+            vec![
+                PathBuf::from("config/default.env"),
+                PathBuf::from(".env.local"),
+            ]
+        );
+    }
+
     #[test]
     fn resolve_upward_path_uses_nearest_ancestor() {
         let root = make_temp_dir("resolve-upward-nearest");
@@ -766,7 +1685,7 @@ mod tests {
         std::fs::write(&root_file, "ROOT=1\n").expect("failed to write root file");
         std::fs::write(&parent_file, "PARENT=1\n").expect("failed to write parent file");
 
-        let resolved = resolve_upward_path(&child, Path::new(".env"));
+        let resolved = resolve_upward_path(&child, Path::new(".env"), &SearchBounds::default());
         assert_eq!(resolved, parent_file);
     }
 
@@ -776,10 +1695,54 @@ mod tests {
         let child = root.join("child");
         std::fs::create_dir_all(&child).expect("failed to create child dir");
 
-        let resolved = resolve_upward_path(&child, Path::new(".env"));
+        let resolved = resolve_upward_path(&child, Path::new(".env"), &SearchBounds::default());
         assert_eq!(resolved, child.join(".env"));
     }
 
+    #[test]
+    fn resolve_upward_path_stops_at_markers_and_max_depth() {
+        let root = make_temp_dir("resolve-upward-bounded");
+        let repo = root.join("repo");
+        let child = repo.join("crates").join("child");
+        std::fs::create_dir_all(&child).expect("failed to create child dir");
+        std::fs::create_dir_all(repo.join(".git")).expect("failed to create marker");
+        std::fs::write(root.join(".env"), "STRAY=1\n").expect("failed to write stray file");
+
+        let unbounded = resolve_upward_path(&child, Path::new(".env"), &SearchBounds::default());
+        assert_eq!(unbounded, root.join(".env"));
+
+        let at_marker = SearchBounds::default().marker(".git");
+        let resolved = resolve_upward_path(&child, Path::new(".env"), &at_marker);
+        assert_eq!(resolved, child.join(".env"));
+
+        std::fs::write(repo.join(".env"), "REPO=1\n").expect("failed to write repo file");
+        let resolved = resolve_upward_path(&child, Path::new(".env"), &at_marker);
+        assert_eq!(resolved, repo.join(".env"));
+
+        let shallow = SearchBounds::default().max_depth(1);
+        let resolved = resolve_upward_path(&child, Path::new(".env"), &shallow);
+        assert_eq!(resolved, child.join(".env"));
+    }
+
+    #[test]
+    fn cascade_upward_paths_lists_outermost_first_within_bounds() {
+        let root = make_temp_dir("cascade-upward");
+        let repo = root.join("repo");
+        let child = repo.join("crates").join("child");
+        std::fs::create_dir_all(&child).expect("failed to create child dir");
+        std::fs::write(repo.join("Cargo.toml"), "").expect("failed to write marker");
+        for dir in [&root, &repo, &child] {
+            std::fs::write(dir.join(".env"), "A=1\n").expect("failed to write env file");
+        }
+
+        let bounds = SearchBounds::default().marker("Cargo.toml");
+        let resolved = cascade_upward_paths(&child, Path::new(".env"), &bounds);
+        assert_eq!(resolved, vec![repo.join(".env"), child.join(".env")]);
+
+        let missing = cascade_upward_paths(&child, Path::new(".env.local"), &bounds);
+        assert_eq!(missing, vec![child.join(".env.local")]);
+    }
+
     #[test]
     fn resolve_upward_path_keeps_absolute_paths() {
         let root = make_temp_dir("resolve-upward-absolute");
@@ -788,10 +1751,14 @@ mod tests {
         let unrelated = root.join("unrelated");
         std::fs::create_dir_all(&unrelated).expect("failed to create unrelated dir");
 
-        let resolved = resolve_upward_path(&unrelated, &absolute);
+        let resolved = resolve_upward_path(&unrelated, &absolute, &SearchBounds::default());
         assert_eq!(resolved, absolute);
     }
 
+    fn effective_paths(loader: &EnvLoader) -> Vec<PathBuf> {
+        loader.watched_paths().expect("paths should resolve")
+    }
+
     fn make_temp_dir(name: &str) -> PathBuf {
         let mut path = std::env::temp_dir();
         let nanos = SystemTime::now()
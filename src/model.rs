@@ -1,28 +1,186 @@
-use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+
+use crate::secret::{REDACTED, SecretString};
 
 /// A parsed `KEY=VALUE` entry from a `.env` file or input buffer.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// `Debug` output redacts the value of secret entries.
+#[derive(Clone, PartialEq, Eq)]
 pub struct Entry {
     pub key: String,
     pub value: String,
     pub source: Option<PathBuf>,
     pub line: u32,
+    /// Whether the value is sensitive, see [`SecretKeys`](crate::SecretKeys).
+    pub secret: bool,
+}
+
+impl Debug for Entry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Entry")
+            .field("key", &self.key)
+            .field("value", &if self.secret { REDACTED } else { &self.value })
+            .field("source", &self.source)
+            .field("line", &self.line)
+            .field("secret", &self.secret)
+            .finish()
+    }
 }
 
 /// Summary of the load operation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct LoadReport {
     pub loaded: usize,
     pub skipped_existing: usize,
     pub files_read: usize,
+    /// Keys the target refused because they are protected.
+    pub blocked: Vec<String>,
 }
 
 /// Result of a safe in-memory load.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// `Debug` output redacts the values of keys in [`LoadedEnv::secrets`].
+#[derive(Clone, PartialEq, Eq)]
 pub struct LoadedEnv {
     pub report: LoadReport,
     pub env: BTreeMap<String, String>,
+    /// File and line each loaded key was read from.
+    pub origins: BTreeMap<String, Origin>,
+    /// Keys in `env` whose values are secret.
+    pub secrets: BTreeSet<String>,
+}
+
+impl LoadedEnv {
+    pub fn is_secret(&self, key: &str) -> bool {
+        self.secrets.contains(key)
+    }
+
+    /// Copy a value into a [`SecretString`].
+    pub fn secret(&self, key: &str) -> Option<SecretString> {
+        self.env
+            .get(key)
+            .map(|value| SecretString::new(value.as_str()))
+    }
+}
+
+impl Debug for LoadedEnv {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let env = self
+            .env
+            .iter()
+            .map(|(key, value)| {
+                let value = if self.is_secret(key) { REDACTED } else { value };
+                (key, value)
+            })
+            .collect::<BTreeMap<_, _>>();
+        f.debug_struct("LoadedEnv")
+            .field("report", &self.report)
+            .field("env", &env)
+            .field("origins", &self.origins)
+            .field("secrets", &self.secrets)
+            .finish()
+    }
+}
+
+/// Location a loaded value was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    pub path: PathBuf,
+    pub line: u32,
+}
+
+/// Where [`EnvLoader::search_upward`](crate::EnvLoader::search_upward) stops
+/// walking parent directories.
+///
+/// The default is unbounded and walks up to the filesystem root. A directory
+/// that ends the search is still searched itself.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchBounds {
+    markers: Vec<String>,
+    max_depth: Option<usize>,
+    stop_at_home: bool,
+    same_filesystem: bool,
+}
+
+impl SearchBounds {
+    /// Stop at the nearest directory containing `.git` or `Cargo.toml`, at
+    /// `$HOME`, and at filesystem boundaries.
+    pub fn project() -> Self {
+        Self::default()
+            .marker(".git")
+            .marker("Cargo.toml")
+            .stop_at_home(true)
+            .same_filesystem(true)
+    }
+
+    /// Stop at the nearest directory containing a file or directory named
+    /// `name`.
+    pub fn marker(mut self, name: impl Into<String>) -> Self {
+        self.markers.push(name.into());
+        self
+    }
+
+    /// Visit at most `depth` parent directories; `0` searches only the
+    /// starting directory.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Stop at `$HOME` when the search starts inside it.
+    pub fn stop_at_home(mut self, stop_at_home: bool) -> Self {
+        self.stop_at_home = stop_at_home;
+        self
+    }
+
+    /// Never cross into a parent directory on another filesystem or device.
+    ///
+    /// Only supported on Unix; ignored elsewhere.
+    pub fn same_filesystem(mut self, same_filesystem: bool) -> Self {
+        self.same_filesystem = same_filesystem;
+        self
+    }
+
+    /// Whether the search must not continue above `dir`, which is `depth`
+    /// levels above the starting directory.
+    pub(crate) fn stops_at(&self, dir: &Path, depth: usize) -> bool {
+        self.max_depth.is_some_and(|max| depth >= max)
+            || self.markers.iter().any(|marker| dir.join(marker).exists())
+            || (self.stop_at_home
+                && std::env::var_os("HOME").is_some_and(|home| Path::new(&home) == dir))
+    }
+
+    /// Whether moving from `dir` to `parent` crosses a filesystem boundary
+    /// that ends the search.
+    #[cfg(unix)]
+    pub(crate) fn blocks_crossing(&self, dir: &Path, parent: &Path) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        if !self.same_filesystem {
+            return false;
+        }
+        let device = |path: &Path| std::fs::metadata(path).map(|metadata| metadata.dev()).ok();
+        device(dir) != device(parent)
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn blocks_crossing(&self, _dir: &Path, _parent: &Path) -> bool {
+        false
+    }
+}
+
+/// Case normalization applied to loaded keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyCase {
+    /// Keep keys as written.
+    #[default]
+    Preserve,
+    /// ASCII-uppercase keys.
+    Upper,
+    /// ASCII-lowercase keys.
+    Lower,
 }
 
 /// Encoding choice for input data.
@@ -45,6 +203,59 @@ pub enum SubstitutionMode {
     Expand,
 }
 
+/// Which value quoting styles are expanded in [`SubstitutionMode::Expand`].
+///
+/// The default expands unquoted, double-quoted, and backtick values and keeps
+/// single-quoted values literal. [`ExpansionPolicy::compose`] matches Docker
+/// Compose, which additionally treats `$$` as an escaped `$`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpansionPolicy {
+    pub unquoted: bool,
+    pub single_quoted: bool,
+    pub double_quoted: bool,
+    pub backtick: bool,
+    /// Treat `$$` as a literal `$`.
+    pub dollar_escape: bool,
+}
+
+impl ExpansionPolicy {
+    /// Docker Compose compatible policy: single-quoted values are literal and
+    /// `$$` is an escaped dollar sign.
+    pub fn compose() -> Self {
+        Self {
+            dollar_escape: true,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for ExpansionPolicy {
+    fn default() -> Self {
+        Self {
+            unquoted: true,
+            single_quoted: false,
+            double_quoted: true,
+            backtick: true,
+            dollar_escape: false,
+        }
+    }
+}
+
+/// How a loader check reacts when it finds a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CheckLevel {
+    /// Do not check.
+    #[default]
+    Ignore,
+    /// Print a warning to stderr unless the loader is quiet.
+    Warn,
+    /// Fail the load. Key checks fail with
+    /// [`Error::Validation`](crate::Error::Validation); file permission checks
+    /// fail with a [`PermissionDenied`](std::io::ErrorKind::PermissionDenied)
+    /// [`Error::Io`](crate::Error::Io).
+    Error,
+}
+
 /// Key validation behavior for parser and loader entry parsing.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum KeyParsingMode {
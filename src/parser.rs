@@ -4,7 +4,8 @@ use std::io::BufRead;
 use std::path::Path;
 
 use crate::error::{Error, ParseError, ParseErrorKind};
-use crate::model::{Entry, KeyParsingMode};
+use crate::model::{Entry, ExpansionPolicy, KeyParsingMode};
+use crate::secret::is_default_secret;
 
 /// Parse dotenv entries from UTF-8 text.
 pub fn parse_str(input: &str) -> Result<Vec<Entry>, Error> {
@@ -16,7 +17,7 @@ pub fn parse_str_with_mode(
     input: &str,
     key_parsing_mode: KeyParsingMode,
 ) -> Result<Vec<Entry>, Error> {
-    parse_str_with_source(input, None, key_parsing_mode, false).map_err(Error::from)
+    parse_str_with_source(input, None, key_parsing_mode, None).map_err(Error::from)
 }
 
 /// Parse dotenv entries from UTF-8 bytes.
@@ -52,7 +53,7 @@ pub(crate) fn parse_str_with_source(
     input: &str,
     source: Option<&Path>,
     key_parsing_mode: KeyParsingMode,
-    preserve_literal_dollar_escapes: bool,
+    expansion: Option<ExpansionPolicy>,
 ) -> Result<Vec<Entry>, ParseError> {
     let normalized = normalize_newlines(input);
     let input = normalized.as_ref();
@@ -60,11 +61,105 @@ pub(crate) fn parse_str_with_source(
     let mut entries = Vec::new();
     let mut by_key = HashMap::<String, usize>::new();
 
+    for statement in statements(input) {
+        let parsed = parse_line(
+            statement.text,
+            statement.line,
+            source,
+            key_parsing_mode,
+            expansion,
+        )?;
+        let Some(entry) = parsed else {
+            continue;
+        };
+
+        if let Some(existing_idx) = by_key.get(&entry.key).copied() {
+            entries[existing_idx] = entry;
+        } else {
+            by_key.insert(entry.key.clone(), entries.len());
+            entries.push(entry);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// An `# @include PATH` directive found by [`parse_str_with_includes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Include {
+    /// Number of entries parsed before the directive.
+    pub(crate) index: usize,
+    pub(crate) path: String,
+    pub(crate) line: u32,
+}
+
+/// Parse entries without collapsing duplicate keys, collecting include
+/// directives and where they occur.
+pub(crate) fn parse_str_with_includes(
+    input: &str,
+    source: Option<&Path>,
+    key_parsing_mode: KeyParsingMode,
+    expansion: Option<ExpansionPolicy>,
+) -> Result<(Vec<Entry>, Vec<Include>), ParseError> {
+    let normalized = normalize_newlines(input);
+    let mut entries = Vec::new();
+    let mut includes = Vec::new();
+    for statement in statements(normalized.as_ref()) {
+        if let Some(path) = include_directive(statement.text) {
+            includes.push(Include {
+                index: entries.len(),
+                path: path.to_owned(),
+                line: statement.line,
+            });
+            continue;
+        }
+        let parsed = parse_line(
+            statement.text,
+            statement.line,
+            source,
+            key_parsing_mode,
+            expansion,
+        )?;
+        entries.extend(parsed);
+    }
+    Ok((entries, includes))
+}
+
+/// The path of an `# @include PATH` comment, with optional quotes removed.
+fn include_directive(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix('#')?.trim_start();
+    let path = rest.strip_prefix("@include")?;
+    if !path.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let path = path.trim();
+    let path = ['"', '\'']
+        .iter()
+        .find_map(|quote| path.strip_prefix(*quote)?.strip_suffix(*quote))
+        .unwrap_or(path);
+    (!path.is_empty()).then_some(path)
+}
+
+/// One logical line of dotenv input; quoted values may span several lines.
+pub(crate) struct Statement<'a> {
+    pub(crate) text: &'a str,
+    /// Byte offset of `text` in the input.
+    #[cfg_attr(not(feature = "encryption"), allow(dead_code))]
+    pub(crate) start: usize,
+    pub(crate) line: u32,
+}
+
+/// Split newline-normalized input into statements.
+pub(crate) fn statements(input: &str) -> impl Iterator<Item = Statement<'_>> {
+    let bytes = input.as_bytes();
     let mut offset = 0usize;
     let mut line_num = 1u32;
-    let bytes = input.as_bytes();
 
-    while offset < bytes.len() {
+    std::iter::from_fn(move || {
+        if offset >= bytes.len() {
+            return None;
+        }
+
         let statement_start = offset;
         let statement_line = line_num;
         let mut idx = offset;
@@ -99,41 +194,21 @@ pub(crate) fn parse_str_with_source(
             idx += 1;
         }
 
-        let statement = &input[statement_start..idx];
-        let parsed = parse_line(
-            statement,
-            statement_line,
-            source,
-            key_parsing_mode,
-            preserve_literal_dollar_escapes,
-        )?;
-        let Some(entry) = parsed else {
-            if idx < bytes.len() && bytes[idx] == b'\n' {
-                idx += 1;
-            }
-            line_num += newline_count;
-            offset = idx;
-            continue;
+        let statement = Statement {
+            text: &input[statement_start..idx],
+            start: statement_start,
+            line: statement_line,
         };
-
-        if let Some(existing_idx) = by_key.get(&entry.key).copied() {
-            entries[existing_idx] = entry;
-        } else {
-            by_key.insert(entry.key.clone(), entries.len());
-            entries.push(entry);
-        }
-
         if idx < bytes.len() && bytes[idx] == b'\n' {
             idx += 1;
         }
         line_num += newline_count;
         offset = idx;
-    }
-
-    Ok(entries)
+        Some(statement)
+    })
 }
 
-fn normalize_newlines(input: &str) -> Cow<'_, str> {
+pub(crate) fn normalize_newlines(input: &str) -> Cow<'_, str> {
     if !input.contains('\r') {
         return Cow::Borrowed(input);
     }
@@ -169,12 +244,12 @@ fn is_preceded_by_odd_backslashes(bytes: &[u8], idx: usize) -> bool {
     backslash_count % 2 == 1
 }
 
-fn parse_line(
+pub(crate) fn parse_line(
     line: &str,
     line_num: u32,
     source: Option<&Path>,
     key_parsing_mode: KeyParsingMode,
-    preserve_literal_dollar_escapes: bool,
+    expansion: Option<ExpansionPolicy>,
 ) -> Result<Option<Entry>, ParseError> {
     let mut working = line.trim_start();
     if working.is_empty() || working.starts_with('#') {
@@ -214,64 +289,77 @@ fn parse_line(
 
     let value_input = working[eq_idx + 1..].trim_start();
     let value_column = (line.len() - value_input.len()) as u32 + 1;
-    let value = parse_value(
-        value_input,
-        line_num,
-        value_column,
-        preserve_literal_dollar_escapes,
-    )?;
+    let value = parse_value(value_input, line_num, value_column, expansion)?;
 
     Ok(Some(Entry {
         key: key.to_owned(),
         value,
         source: source.map(Path::to_path_buf),
         line: line_num,
+        secret: is_default_secret(key),
     }))
 }
 
-fn parse_value(
-    input: &str,
-    line_num: u32,
-    column: u32,
-    preserve_literal_dollar_escapes: bool,
-) -> Result<String, ParseError> {
-    if input.is_empty() {
-        return Ok(String::new());
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QuoteStyle {
+    Unquoted,
+    Single,
+    Double,
+    Backtick,
+}
 
-    if input.starts_with('\'') {
-        return parse_single_quoted(input, line_num, column, preserve_literal_dollar_escapes);
-    }
-    if input.starts_with('"') {
-        return parse_double_quoted(input, line_num, column, preserve_literal_dollar_escapes);
-    }
-    if input.starts_with('`') {
-        return parse_backtick_quoted(input, line_num, column);
+impl QuoteStyle {
+    fn of(input: &str) -> Self {
+        match input.as_bytes().first() {
+            Some(b'\'') => Self::Single,
+            Some(b'"') => Self::Double,
+            Some(b'`') => Self::Backtick,
+            _ => Self::Unquoted,
+        }
     }
 
-    let value = input
-        .split_once('#')
-        .map(|(head, _)| head)
-        .unwrap_or(input)
-        .trim_end();
-    Ok(value.to_owned())
+    fn expands(self, policy: ExpansionPolicy) -> bool {
+        match self {
+            Self::Unquoted => policy.unquoted,
+            Self::Single => policy.single_quoted,
+            Self::Double => policy.double_quoted,
+            Self::Backtick => policy.backtick,
+        }
+    }
 }
 
-fn parse_single_quoted(
+/// Parse a raw value.
+///
+/// When `expansion` is set, values whose quote style is not expanded have
+/// every `$` escaped so the substitution pass keeps them literal.
+fn parse_value(
     input: &str,
     line_num: u32,
     column: u32,
-    preserve_literal_dollar_escapes: bool,
+    expansion: Option<ExpansionPolicy>,
 ) -> Result<String, ParseError> {
-    let parsed = parse_literal_quoted(input, '\'', line_num, column)?;
-    if !preserve_literal_dollar_escapes {
-        return Ok(parsed);
+    if input.is_empty() {
+        return Ok(String::new());
     }
-    Ok(escape_dollar_signs(&parsed))
-}
 
-fn parse_backtick_quoted(input: &str, line_num: u32, column: u32) -> Result<String, ParseError> {
-    parse_literal_quoted(input, '`', line_num, column)
+    let style = QuoteStyle::of(input);
+    let expands = expansion.map(|policy| style.expands(policy));
+    let value = match style {
+        QuoteStyle::Single => parse_literal_quoted(input, '\'', line_num, column)?,
+        QuoteStyle::Double => parse_double_quoted(input, line_num, column, expands == Some(true))?,
+        QuoteStyle::Backtick => parse_literal_quoted(input, '`', line_num, column)?,
+        QuoteStyle::Unquoted => input
+            .split_once('#')
+            .map(|(head, _)| head)
+            .unwrap_or(input)
+            .trim_end()
+            .to_owned(),
+    };
+
+    if expands == Some(false) {
+        return Ok(escape_dollar_signs(&value));
+    }
+    Ok(value)
 }
 
 fn parse_literal_quoted(
@@ -406,6 +494,32 @@ fn is_valid_permissive_key_char(ch: char) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn include_directives_are_collected_in_order() {
+        let input = "A=1\n# @include ./shared.env\n#@include \"b c.env\"\n# @included x\nA=2\n";
+        let (entries, includes) =
+            parse_str_with_includes(input, None, KeyParsingMode::Strict, None)
+                .expect("parse should succeed");
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            includes,
+            vec![
+                Include {
+                    index: 1,
+                    path: "./shared.env".to_owned(),
+                    line: 2,
+                },
+                Include {
+                    index: 1,
+                    path: "b c.env".to_owned(),
+                    line: 3,
+                },
+            ]
+        );
+        assert_eq!(include_directive("# @include"), None);
+    }
+
     #[test]
     fn parses_basic_values_and_comments() {
         let input = "A=1\nB = 2\n# skip\nC=hello # comment\nD=\n";
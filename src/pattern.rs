@@ -0,0 +1,292 @@
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+/// Key selector for [`EnvLoader::include_key`](crate::EnvLoader::include_key)
+/// and [`EnvLoader::exclude_key`](crate::EnvLoader::exclude_key).
+///
+/// Non-exhaustive because the `Regex` variant only exists with the `regex`
+/// feature.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum KeyPattern {
+    /// Keys starting with this prefix.
+    Prefix(String),
+    /// Shell-style glob where `*` matches any run of characters and `?`
+    /// matches one character.
+    Glob(String),
+    /// Keys matching a regular expression anywhere; use `^`/`$` to anchor.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl KeyPattern {
+    /// Compile `pattern` into a [`KeyPattern::Regex`].
+    #[cfg(feature = "regex")]
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        regex::Regex::new(pattern).map(Self::Regex)
+    }
+
+    pub fn matches(&self, key: &str) -> bool {
+        match self {
+            Self::Prefix(prefix) => key.starts_with(prefix.as_str()),
+            Self::Glob(glob) => glob_match(glob, key),
+            #[cfg(feature = "regex")]
+            Self::Regex(pattern) => pattern.is_match(key),
+        }
+    }
+}
+
+impl PartialEq for KeyPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Prefix(left), Self::Prefix(right)) => left == right,
+            (Self::Glob(left), Self::Glob(right)) => left == right,
+            #[cfg(feature = "regex")]
+            (Self::Regex(left), Self::Regex(right)) => left.as_str() == right.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for KeyPattern {}
+
+/// Match `text` against a glob supporting `*` and `?`.
+pub(crate) fn glob_match(glob: &str, text: &str) -> bool {
+    if glob.is_ascii() && text.is_ascii() {
+        return glob_match_units(glob.as_bytes(), text.as_bytes(), b'*', b'?');
+    }
+    let glob = glob.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    glob_match_units(&glob, &text, '*', '?')
+}
+
+fn glob_match_units<T: Copy + PartialEq>(glob: &[T], text: &[T], any_run: T, any_one: T) -> bool {
+    let (mut g, mut t) = (0, 0);
+    // Position after the last `*` and the text index it is currently absorbing.
+    let mut backtrack = None;
+    while t < text.len() {
+        match glob.get(g) {
+            Some(&unit) if unit == any_run => {
+                g += 1;
+                backtrack = Some((g, t));
+            }
+            Some(&unit) if unit == any_one || unit == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_g, star_t)) => {
+                    g = star_g;
+                    t = star_t + 1;
+                    backtrack = Some((star_g, t));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|unit| *unit == any_run)
+}
+
+/// Whether `path` uses glob syntax: `*`, `?`, or `{a,b}` alternatives.
+///
+/// Braces without a comma, as in `{name}`, are literal.
+pub(crate) fn is_path_glob(path: &str) -> bool {
+    path.contains(['*', '?']) || expand_braces(path).len() > 1
+}
+
+/// Files matching the path glob `pattern`.
+///
+/// `{a,b}` alternatives expand in written order; the matches of each
+/// alternative are sorted, and later duplicates are dropped. `*` and `?` match
+/// within one path component and skip hidden names unless the component
+/// itself starts with `.`.
+pub(crate) fn glob_paths(pattern: &str) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for alternative in expand_braces(pattern) {
+        let mut matches = glob_components(Path::new(&alternative))?;
+        matches.sort();
+        for path in matches {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+fn glob_components(pattern: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut candidates = vec![PathBuf::new()];
+    for component in pattern.components() {
+        let glob = match component {
+            Component::Normal(name) => name.to_str().filter(|name| name.contains(['*', '?'])),
+            _ => None,
+        };
+        let Some(glob) = glob else {
+            for candidate in &mut candidates {
+                candidate.push(component);
+            }
+            continue;
+        };
+
+        let mut matched = Vec::new();
+        for base in &candidates {
+            let dir = if base.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                base.as_path()
+            };
+            let entries = match std::fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(err)
+                    if matches!(err.kind(), ErrorKind::NotFound | ErrorKind::NotADirectory) =>
+                {
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            for entry in entries {
+                let name = entry?.file_name();
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+                if (glob.starts_with('.') || !name.starts_with('.')) && glob_match(glob, name) {
+                    matched.push(base.join(name));
+                }
+            }
+        }
+        candidates = matched;
+    }
+    candidates.retain(|candidate| candidate.is_file());
+    Ok(candidates)
+}
+
+/// Expand `{a,b}` groups, recursively, keeping written order. Groups without
+/// a top-level comma are kept literally.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_owned()];
+    };
+    let mut depth = 0;
+    let mut splits = vec![open];
+    let mut close = None;
+    for (idx, ch) in pattern[open..]
+        .char_indices()
+        .map(|(idx, ch)| (open + idx, ch))
+    {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(idx);
+                    break;
+                }
+            }
+            ',' if depth == 1 => splits.push(idx),
+            _ => {}
+        }
+    }
+    let Some(close) = close else {
+        return vec![pattern.to_owned()];
+    };
+    if splits.len() == 1 {
+        let literal = &pattern[..=close];
+        return expand_braces(&pattern[close + 1..])
+            .into_iter()
+            .map(|rest| format!("{literal}{rest}"))
+            .collect();
+    }
+    splits.push(close);
+
+    let (prefix, suffix) = (&pattern[..open], &pattern[close + 1..]);
+    splits
+        .windows(2)
+        .flat_map(|bounds| {
+            let alternative = &pattern[bounds[0] + 1..bounds[1]];
+            expand_braces(&format!("{prefix}{alternative}{suffix}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyPattern, expand_braces, glob_match, glob_paths, is_path_glob};
+
+    #[test]
+    fn globs_match_wildcards() {
+        assert!(glob_match("BILLING_*", "BILLING_URL"));
+        assert!(glob_match("*_TOKEN", "GITHUB_TOKEN"));
+        assert!(glob_match("*PASS*", "DB_PASSWORD"));
+        assert!(glob_match("A?C", "ABC"));
+        assert!(!glob_match("A?C", "AC"));
+        assert!(!glob_match("BILLING_*", "SHIPPING_URL"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("Ü?*", "ÜBER"));
+    }
+
+    #[test]
+    fn braces_expand_in_written_order() {
+        assert_eq!(
+            expand_braces(".env.{shared,local}"),
+            vec![".env.shared", ".env.local"]
+        );
+        assert_eq!(expand_braces("{a,b{1,2}}/x"), vec!["a/x", "b1/x", "b2/x"]);
+        assert_eq!(expand_braces("plain{"), vec!["plain{"]);
+        assert_eq!(
+            expand_braces("{app}/.env.{a,b}"),
+            vec!["{app}/.env.a", "{app}/.env.b"]
+        );
+    }
+
+    #[test]
+    fn braces_need_a_comma_to_be_a_glob() {
+        assert!(is_path_glob(".env.{shared,local}"));
+        assert!(is_path_glob("config/*.env"));
+        assert!(!is_path_glob("config/{app}.env"));
+        assert!(!is_path_glob("plain{"));
+        assert!(!is_path_glob(".env"));
+    }
+
+    #[test]
+    fn path_globs_match_sorted_files() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "dotenvor-pattern-glob-{}-{nanos}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join("config")).expect("failed to create dir");
+        for name in ["b.env", "a.env", ".hidden.env", "notes.txt"] {
+            std::fs::write(dir.join("config").join(name), "").expect("failed to write file");
+        }
+
+        let pattern = format!("{}/config/*.env", dir.display());
+        assert_eq!(
+            glob_paths(&pattern).unwrap(),
+            vec![dir.join("config/a.env"), dir.join("config/b.env")]
+        );
+        let pattern = format!("{}/*/{{b,a,missing}}.env", dir.display());
+        assert_eq!(
+            glob_paths(&pattern).unwrap(),
+            vec![dir.join("config/b.env"), dir.join("config/a.env")]
+        );
+    }
+
+    #[test]
+    fn key_patterns_dispatch_by_kind() {
+        assert!(KeyPattern::Prefix("APP_".into()).matches("APP_NAME"));
+        assert!(!KeyPattern::Prefix("APP_".into()).matches("MY_APP_NAME"));
+        assert!(KeyPattern::Glob("*_URL".into()).matches("DB_URL"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_key_patterns_match_unanchored() {
+        let regex = KeyPattern::regex("(DB|CACHE)_").expect("pattern should compile");
+        assert!(regex.matches("APP_CACHE_HOST"));
+        assert_eq!(regex, KeyPattern::regex("(DB|CACHE)_").unwrap());
+        assert!(KeyPattern::regex("(abc").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use std::fs::File;
+use std::path::Path;
+
+/// Permission and ownership facts for an opened dotenv file.
+///
+/// Taken from the open handle, so the checks apply to the file actually
+/// read even if `path` is swapped afterwards. Always clean on non-Unix
+/// platforms.
+pub(crate) struct FileSecurity {
+    #[cfg(unix)]
+    mode: u32,
+    #[cfg(unix)]
+    owner: u32,
+    /// Owner of `path` itself when it is a symlink.
+    #[cfg(unix)]
+    link_owner: Option<u32>,
+}
+
+#[cfg(unix)]
+impl FileSecurity {
+    pub(crate) fn inspect(path: &Path, file: &File) -> std::io::Result<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = file.metadata()?;
+        let link = std::fs::symlink_metadata(path)?;
+        Ok(Self {
+            mode: metadata.mode() & 0o777,
+            owner: metadata.uid(),
+            link_owner: link.file_type().is_symlink().then(|| link.uid()),
+        })
+    }
+
+    /// Problems known before reading: group- or world-writable files, and
+    /// files or symlinks owned by neither the current user nor root.
+    pub(crate) fn problems(&self) -> Vec<String> {
+        // SAFETY: `geteuid` has no preconditions and cannot fail.
+        let euid = unsafe { libc::geteuid() };
+        let foreign = |owner: u32| owner != euid && owner != 0;
+        let mode = self.mode;
+        let mut problems = Vec::new();
+        if mode & 0o022 != 0 {
+            problems.push(format!("is group- or world-writable (mode {mode:03o})"));
+        }
+        if foreign(self.owner) {
+            problems.push(format!(
+                "is owned by uid {}, not the current user",
+                self.owner
+            ));
+        }
+        if let Some(owner) = self.link_owner.filter(|owner| foreign(*owner)) {
+            problems.push(format!(
+                "is a symlink owned by uid {owner}, not the current user"
+            ));
+        }
+        problems
+    }
+
+    /// Problems for a file found to hold plaintext secrets.
+    pub(crate) fn secret_problems(&self) -> Vec<String> {
+        let mode = self.mode;
+        if mode & 0o044 == 0 {
+            return Vec::new();
+        }
+        vec![format!(
+            "holds secrets but is group- or world-readable (mode {mode:03o})"
+        )]
+    }
+}
+
+#[cfg(not(unix))]
+impl FileSecurity {
+    pub(crate) fn inspect(_path: &Path, _file: &File) -> std::io::Result<Self> {
+        Ok(Self {})
+    }
+
+    pub(crate) fn problems(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    pub(crate) fn secret_problems(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::fs::File;
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::FileSecurity;
+
+    #[test]
+    fn writable_and_readable_modes_are_reported() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!(
+            "dotenvor-permissions-{}-{nanos}.env",
+            std::process::id()
+        ));
+        std::fs::write(&path, "API_TOKEN=secret\n").expect("failed to write test file");
+        let inspect = |mode| {
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))
+                .expect("failed to set permissions");
+            let file = File::open(&path).expect("failed to open test file");
+            FileSecurity::inspect(&path, &file).expect("failed to inspect test file")
+        };
+
+        let security = inspect(0o600);
+        assert!(security.problems().is_empty());
+        assert!(security.secret_problems().is_empty());
+
+        let security = inspect(0o644);
+        assert!(security.problems().is_empty());
+        let problems = security.secret_problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("readable (mode 644)"));
+
+        let problems = inspect(0o666).problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("writable (mode 666)"));
+
+        let link = path.with_extension("link");
+        std::os::unix::fs::symlink(&path, &link).expect("failed to create symlink");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .expect("failed to set permissions");
+        let file = File::open(&link).expect("failed to open symlink");
+        let security = FileSecurity::inspect(&link, &file).expect("failed to inspect symlink");
+        assert!(security.problems().is_empty());
+
+        std::fs::remove_file(&link).expect("failed to remove symlink");
+        std::fs::remove_file(&path).expect("failed to remove test file");
+    }
+}
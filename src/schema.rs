@@ -0,0 +1,438 @@
+use std::path::Path;
+
+use crate::error::{Error, ParseError, ParseErrorKind, ValidationError, Violation, ViolationKind};
+use crate::model::KeyParsingMode;
+use crate::parser::parse_str_with_source;
+use crate::substitution::VarLookup;
+
+/// Declared value type for a [`SchemaVar`].
+///
+/// Non-exhaustive because the `Regex` variant only exists with the `regex`
+/// feature.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub enum VarType {
+    /// Any value.
+    #[default]
+    String,
+    /// A signed 64-bit integer.
+    Int,
+    /// `true`/`false`, `1`/`0`, `yes`/`no`, or `on`/`off` (case-insensitive).
+    Bool,
+    /// An absolute URL such as `postgres://db:5432/app`.
+    Url,
+    /// One of a fixed set of values.
+    Enum(Vec<String>),
+    /// A value matching a regular expression anywhere; use `^`/`$` to anchor.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl PartialEq for VarType {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::String, Self::String)
+            | (Self::Int, Self::Int)
+            | (Self::Bool, Self::Bool)
+            | (Self::Url, Self::Url) => true,
+            (Self::Enum(left), Self::Enum(right)) => left == right,
+            #[cfg(feature = "regex")]
+            (Self::Regex(left), Self::Regex(right)) => left.as_str() == right.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for VarType {}
+
+impl VarType {
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            Self::String => true,
+            Self::Int => value.parse::<i64>().is_ok(),
+            Self::Bool => parse_bool(value).is_some(),
+            Self::Url => is_url(value),
+            Self::Enum(choices) => choices.iter().any(|choice| choice == value),
+            #[cfg(feature = "regex")]
+            Self::Regex(pattern) => pattern.is_match(value),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::String => "string".to_owned(),
+            Self::Int => "int".to_owned(),
+            Self::Bool => "bool".to_owned(),
+            Self::Url => "url".to_owned(),
+            Self::Enum(choices) => format!("one of {}", choices.join(", ")),
+            #[cfg(feature = "regex")]
+            Self::Regex(pattern) => format!("value matching `{pattern}`"),
+        }
+    }
+}
+
+/// Declaration of a single variable in a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaVar {
+    pub key: String,
+    pub var_type: VarType,
+    pub required: bool,
+    pub default: Option<String>,
+    pub description: Option<String>,
+    /// Treat the value as secret regardless of [`SecretKeys`](crate::SecretKeys).
+    pub secret: bool,
+}
+
+impl SchemaVar {
+    /// Declare an optional string variable.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            var_type: VarType::String,
+            required: false,
+            default: None,
+            description: None,
+            secret: false,
+        }
+    }
+
+    pub fn var_type(mut self, var_type: VarType) -> Self {
+        self.var_type = var_type;
+        self
+    }
+
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Value applied when the variable is not set anywhere.
+    pub fn default(mut self, default: impl Into<String>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn secret(mut self, secret: bool) -> Self {
+        self.secret = secret;
+        self
+    }
+}
+
+/// Declared variables checked after a load is merged.
+///
+/// Build a schema in code with [`Schema::var`], or parse an annotated dotenv
+/// file with [`Schema::parse_str`]/[`Schema::from_path`]:
+///
+/// ```text
+/// # Primary database connection.
+/// # @required
+/// # @type url
+/// DATABASE_URL=
+///
+/// # @type enum(debug, info, warn, error)
+/// # @default info
+/// LOG_LEVEL=
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Schema {
+    vars: Vec<SchemaVar>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a variable declaration, replacing any previous one for the same key.
+    pub fn var(mut self, var: SchemaVar) -> Self {
+        if let Some(existing) = self.vars.iter_mut().find(|item| item.key == var.key) {
+            *existing = var;
+        } else {
+            self.vars.push(var);
+        }
+        self
+    }
+
+    pub fn vars(&self) -> &[SchemaVar] {
+        &self.vars
+    }
+
+    pub fn get(&self, key: &str) -> Option<&SchemaVar> {
+        self.vars.iter().find(|var| var.key == key)
+    }
+
+    /// Parse a schema from annotated dotenv text.
+    ///
+    /// Comment lines directly above a key describe it. Lines of the form
+    /// `# @required`, `# @optional`, `# @secret`, `# @type <type>`,
+    /// `# @default <value>`, and `# @description <text>` are annotations;
+    /// other comment lines form the description, which `@description`
+    /// replaces. Types are `string`, `int`, `bool`, `url`, `enum(a, b)`, and,
+    /// with the `regex` feature, `regex(<pattern>)`. Values in the file
+    /// itself are ignored.
+    pub fn parse_str(input: &str) -> Result<Self, Error> {
+        let entries = parse_str_with_source(input, None, KeyParsingMode::Strict, None)?;
+        let lines = input.lines().collect::<Vec<_>>();
+
+        let mut schema = Self::new();
+        for entry in entries {
+            let mut var = SchemaVar::new(entry.key);
+            let start = comment_block_start(&lines, entry.line);
+            let mut description = Vec::new();
+            for (offset, line) in lines[start..entry.line as usize - 1].iter().enumerate() {
+                let line_num = (start + offset + 1) as u32;
+                let comment = line.trim_start().trim_start_matches('#').trim();
+                match comment.strip_prefix('@') {
+                    Some(annotation) => apply_annotation(&mut var, annotation, line_num)?,
+                    None if !comment.is_empty() => description.push(comment),
+                    None => {}
+                }
+            }
+            if !description.is_empty() && var.description.is_none() {
+                var.description = Some(description.join(" "));
+            }
+            schema = schema.var(var);
+        }
+        Ok(schema)
+    }
+
+    /// Read and parse an annotated schema file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_str(&content)
+    }
+
+    /// Check `lookup` against every declaration and report all violations.
+    ///
+    /// A required variable that is set to an empty value counts as missing.
+    pub fn validate<L: VarLookup + ?Sized>(&self, lookup: &L) -> Result<(), ValidationError> {
+        self.check(lookup).map(|_| ())
+    }
+
+    /// Validate and return the defaults that should be applied for unset keys.
+    pub(crate) fn check<L: VarLookup + ?Sized>(
+        &self,
+        lookup: &L,
+    ) -> Result<Vec<(String, String)>, ValidationError> {
+        let mut defaults = Vec::new();
+        let mut violations = Vec::new();
+        for var in &self.vars {
+            let value = match lookup.lookup(&var.key) {
+                Some(value) if value.is_empty() && var.required => {
+                    violations.push(Violation {
+                        key: var.key.clone(),
+                        kind: ViolationKind::Missing,
+                    });
+                    continue;
+                }
+                Some(value) => value,
+                None => match &var.default {
+                    Some(default) => {
+                        defaults.push((var.key.clone(), default.clone()));
+                        default.clone()
+                    }
+                    None if var.required => {
+                        violations.push(Violation {
+                            key: var.key.clone(),
+                            kind: ViolationKind::Missing,
+                        });
+                        continue;
+                    }
+                    None => continue,
+                },
+            };
+
+            if !var.var_type.accepts(&value) {
+                violations.push(Violation {
+                    key: var.key.clone(),
+                    kind: ViolationKind::InvalidValue {
+                        expected: var.var_type.describe(),
+                    },
+                });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(defaults)
+        } else {
+            Err(ValidationError { violations })
+        }
+    }
+}
+
+/// Index of the first line of the contiguous comment block above `entry_line`.
+fn comment_block_start(lines: &[&str], entry_line: u32) -> usize {
+    let mut start = entry_line as usize - 1;
+    while start > 0 && lines[start - 1].trim_start().starts_with('#') {
+        start -= 1;
+    }
+    start
+}
+
+fn apply_annotation(var: &mut SchemaVar, annotation: &str, line_num: u32) -> Result<(), Error> {
+    let (name, argument) = annotation
+        .split_once(char::is_whitespace)
+        .map(|(name, argument)| (name, argument.trim()))
+        .unwrap_or((annotation, ""));
+    let invalid = || {
+        Error::from(ParseError::new(
+            line_num,
+            1,
+            ParseErrorKind::InvalidAnnotation,
+        ))
+    };
+
+    match (name, argument) {
+        ("required", "") => var.required = true,
+        ("optional", "") => var.required = false,
+        ("secret", "") => var.secret = true,
+        ("default", _) => var.default = Some(argument.to_owned()),
+        ("description", _) => var.description = Some(argument.to_owned()),
+        ("type", _) => var.var_type = parse_var_type(argument).ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    }
+    Ok(())
+}
+
+fn parse_var_type(input: &str) -> Option<VarType> {
+    match input {
+        "string" => return Some(VarType::String),
+        "int" => return Some(VarType::Int),
+        "bool" => return Some(VarType::Bool),
+        "url" => return Some(VarType::Url),
+        _ => {}
+    }
+
+    if let Some(choices) = input
+        .strip_prefix("enum(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let choices = choices
+            .split(',')
+            .map(|choice| choice.trim().to_owned())
+            .collect::<Vec<_>>();
+        if choices.iter().any(String::is_empty) {
+            return None;
+        }
+        return Some(VarType::Enum(choices));
+    }
+
+    #[cfg(feature = "regex")]
+    if let Some(pattern) = input
+        .strip_prefix("regex(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return regex::Regex::new(pattern).ok().map(VarType::Regex);
+    }
+    None
+}
+
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn is_url(value: &str) -> bool {
+    let Some((scheme, rest)) = value.split_once("://") else {
+        return false;
+    };
+    let mut scheme_chars = scheme.chars();
+    scheme_chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic())
+        && scheme_chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'))
+        && !rest.is_empty()
+        && !rest.chars().any(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Schema, SchemaVar, VarType, is_url, parse_var_type};
+    use crate::error::{Error, ParseErrorKind};
+
+    #[test]
+    fn parses_annotations_and_descriptions() {
+        let schema = Schema::parse_str(
+            "# Primary database.\n\
+             # @required\n\
+             # @type url\n\
+             DATABASE_URL=postgres://example\n\
+             \n\
+             # unrelated comment\n\
+             \n\
+             # Verbosity.\n\
+             # @type enum(debug, info)\n\
+             # @default info\n\
+             # @description Minimum log level.\n\
+             LOG_LEVEL=\n\
+             # @secret\n\
+             SIGNING_SEED=\n\
+             PLAIN=\n",
+        )
+        .expect("schema should parse");
+
+        assert_eq!(
+            schema.vars(),
+            &[
+                SchemaVar::new("DATABASE_URL")
+                    .required(true)
+                    .var_type(VarType::Url)
+                    .description("Primary database."),
+                SchemaVar::new("LOG_LEVEL")
+                    .var_type(VarType::Enum(vec!["debug".into(), "info".into()]))
+                    .default("info")
+                    .description("Minimum log level."),
+                SchemaVar::new("SIGNING_SEED").secret(true),
+                SchemaVar::new("PLAIN"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_annotations() {
+        let err = Schema::parse_str("A=1\n# @type float\nB=\n").expect_err("expected error");
+        match err {
+            Error::Parse(parse_err) => {
+                assert_eq!(parse_err.kind, ParseErrorKind::InvalidAnnotation);
+                assert_eq!(parse_err.line, 2);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn parses_regex_types() {
+        assert_eq!(
+            parse_var_type("regex(^[a-z]+(-[a-z]+)*$)"),
+            Some(VarType::Regex(
+                regex::Regex::new("^[a-z]+(-[a-z]+)*$").expect("pattern should compile")
+            ))
+        );
+        assert_eq!(parse_var_type("regex([a-)"), None);
+    }
+
+    #[cfg(not(feature = "regex"))]
+    #[test]
+    fn regex_types_need_the_regex_feature() {
+        assert_eq!(parse_var_type("regex(^[a-z]+$)"), None);
+    }
+
+    #[test]
+    fn url_requires_scheme_and_location() {
+        assert!(is_url("https://example.com/path"));
+        assert!(is_url("postgres+ssl://db:5432"));
+        assert!(!is_url("example.com"));
+        assert!(!is_url("1http://example.com"));
+        assert!(!is_url("https://"));
+        assert!(!is_url("https://exa mple.com"));
+    }
+}
@@ -0,0 +1,188 @@
+use std::borrow::Cow;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{Ordering, compiler_fence};
+
+use crate::pattern::{KeyPattern, glob_match};
+
+/// Key globs treated as secret by [`SecretKeys::default`].
+const DEFAULT_SECRET_PATTERNS: &[&str] = &[
+    "*SECRET*",
+    "*TOKEN*",
+    "*PASSWORD*",
+    "*PASSWD*",
+    "*PRIVATE_KEY*",
+    "*API_KEY*",
+    "*CREDENTIAL*",
+];
+
+/// Placeholder printed instead of secret values.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// Whether `key` matches the default secret patterns.
+pub(crate) fn is_default_secret(key: &str) -> bool {
+    let key = uppercase(key);
+    DEFAULT_SECRET_PATTERNS
+        .iter()
+        .any(|pattern| glob_match(pattern, &key))
+}
+
+fn uppercase(key: &str) -> Cow<'_, str> {
+    if key.bytes().any(|byte| byte.is_ascii_lowercase()) {
+        Cow::Owned(key.to_ascii_uppercase())
+    } else {
+        Cow::Borrowed(key)
+    }
+}
+
+/// Classification of keys whose values must not be printed.
+///
+/// [`SecretKeys::default`] matches keys containing `SECRET`, `TOKEN`,
+/// `PASSWORD`, `PASSWD`, `PRIVATE_KEY`, `API_KEY`, or `CREDENTIAL`. Patterns
+/// are tried against the key as written and in ASCII uppercase, and public
+/// patterns take precedence over secret ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretKeys {
+    secret: Vec<KeyPattern>,
+    public: Vec<KeyPattern>,
+}
+
+impl Default for SecretKeys {
+    fn default() -> Self {
+        Self {
+            secret: DEFAULT_SECRET_PATTERNS
+                .iter()
+                .map(|pattern| KeyPattern::Glob((*pattern).to_owned()))
+                .collect(),
+            public: Vec::new(),
+        }
+    }
+}
+
+impl SecretKeys {
+    /// Treat no key as secret unless marked explicitly.
+    pub fn none() -> Self {
+        Self {
+            secret: Vec::new(),
+            public: Vec::new(),
+        }
+    }
+
+    pub fn secret(mut self, pattern: KeyPattern) -> Self {
+        self.secret.push(pattern);
+        self
+    }
+
+    /// Never treat keys matching `pattern` as secret.
+    pub fn public(mut self, pattern: KeyPattern) -> Self {
+        self.public.push(pattern);
+        self
+    }
+
+    pub fn is_secret(&self, key: &str) -> bool {
+        let upper = uppercase(key);
+        let matches = |pattern: &KeyPattern| pattern.matches(key) || pattern.matches(&upper);
+        self.secret.iter().any(matches) && !self.public.iter().any(matches)
+    }
+}
+
+/// A string whose `Debug` output is redacted and whose buffer is zeroed on
+/// drop.
+///
+/// Zeroing only covers the final allocation; copies made before wrapping the
+/// value, or by earlier reallocations, are outside its reach.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SecretString").field(&REDACTED).finish()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_owned())
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        // SAFETY: the buffer is only overwritten with zero bytes, which are
+        // valid UTF-8, and the string is dropped right after.
+        let bytes = unsafe { self.0.as_mut_vec() };
+        for byte in bytes.iter_mut() {
+            // SAFETY: `byte` is a valid, aligned reference into the buffer.
+            unsafe { std::ptr::write_volatile(byte, 0) };
+        }
+        for byte in bytes.spare_capacity_mut() {
+            // SAFETY: spare capacity is allocated memory owned by the buffer.
+            unsafe { std::ptr::write_volatile(byte.as_mut_ptr(), 0) };
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SecretString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SecretKeys, SecretString, is_default_secret};
+    use crate::pattern::KeyPattern;
+
+    #[test]
+    fn default_patterns_match_common_secret_keys() {
+        for key in [
+            "API_SECRET",
+            "GITHUB_TOKEN",
+            "db_password",
+            "STRIPE_API_KEY",
+        ] {
+            assert!(is_default_secret(key), "{key} should be secret");
+            assert!(
+                SecretKeys::default().is_secret(key),
+                "{key} should be secret"
+            );
+        }
+        for key in ["DATABASE_URL", "PORT", "KEYBOARD"] {
+            assert!(!is_default_secret(key), "{key} should not be secret");
+        }
+    }
+
+    #[test]
+    fn public_patterns_override_secret_ones() {
+        let keys = SecretKeys::default()
+            .secret(KeyPattern::Prefix("VAULT_".into()))
+            .public(KeyPattern::Glob("TOKEN_URL".into()));
+        assert!(keys.is_secret("VAULT_ADDR"));
+        assert!(!keys.is_secret("TOKEN_URL"));
+        assert!(!SecretKeys::none().is_secret("API_SECRET"));
+    }
+
+    #[test]
+    fn secret_string_debug_is_redacted() {
+        let secret = SecretString::new("hunter2");
+        assert_eq!(format!("{secret:?}"), "SecretString(\"[REDACTED]\")");
+        assert_eq!(secret.expose_secret(), "hunter2");
+    }
+}
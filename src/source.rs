@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+use std::fmt::{Debug, Formatter};
+use std::io::{self, Read};
+use std::sync::{Mutex, PoisonError};
+
+/// Non-file input for [`EnvLoader::source`](crate::EnvLoader::source).
+///
+/// Sources merge with files in the order they were added, so later inputs
+/// win. A [`io::ErrorKind::NotFound`] error from [`EnvSource::read`] counts
+/// as a missing input and follows [`EnvLoader::required`](crate::EnvLoader::required).
+pub trait EnvSource: Send + Sync {
+    /// Name recorded as [`Entry::source`](crate::Entry::source) and shown in
+    /// logs and errors.
+    fn name(&self) -> Cow<'_, str>;
+
+    /// Raw dotenv content, decoded with the loader's
+    /// [`Encoding`](crate::Encoding).
+    fn read(&self) -> io::Result<Vec<u8>>;
+}
+
+impl Debug for dyn EnvSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("EnvSource").field(&self.name()).finish()
+    }
+}
+
+/// In-memory text added with [`EnvLoader::source_str`](crate::EnvLoader::source_str).
+pub(crate) struct StrSource {
+    name: String,
+    text: Cow<'static, str>,
+}
+
+impl StrSource {
+    pub(crate) fn new(name: String, text: Cow<'static, str>) -> Self {
+        Self { name, text }
+    }
+}
+
+impl EnvSource for StrSource {
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.name)
+    }
+
+    fn read(&self) -> io::Result<Vec<u8>> {
+        Ok(self.text.as_bytes().to_vec())
+    }
+}
+
+/// Reader added with [`EnvLoader::source_reader`](crate::EnvLoader::source_reader).
+///
+/// The reader is drained on first use and its content kept, so loading the
+/// same source twice sees the same entries.
+pub(crate) struct ReaderSource {
+    name: String,
+    state: Mutex<ReaderState>,
+}
+
+enum ReaderState {
+    Pending(Box<dyn Read + Send>),
+    Read(Vec<u8>),
+}
+
+impl ReaderSource {
+    pub(crate) fn new(name: String, reader: Box<dyn Read + Send>) -> Self {
+        Self {
+            name,
+            state: Mutex::new(ReaderState::Pending(reader)),
+        }
+    }
+}
+
+impl EnvSource for ReaderSource {
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.name)
+    }
+
+    fn read(&self) -> io::Result<Vec<u8>> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let ReaderState::Pending(reader) = &mut *state {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            *state = ReaderState::Read(bytes);
+        }
+        match &*state {
+            ReaderState::Read(bytes) => Ok(bytes.clone()),
+            ReaderState::Pending(_) => unreachable!("reader drained above"),
+        }
+    }
+}
@@ -0,0 +1,870 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::env::TargetEnv;
+use crate::model::{Entry, KeyParsingMode, LoadedEnv};
+
+type BuiltinProvider = fn(&str) -> Option<String>;
+
+/// Providers registered by [`ReferenceRegistry::new`] and
+/// `builtin_references(true)`.
+const BUILTIN_PROVIDERS: [(&str, BuiltinProvider); 3] = [
+    ("file", resolve_file_reference),
+    ("env", resolve_env_reference),
+    ("base64", resolve_base64_reference),
+];
+
+/// Expand `$VAR`, `${VAR}`, `${VAR:-fallback}`, and `${prefix:reference}`
+/// placeholders in `template`.
+///
+/// This uses the same grammar as [`SubstitutionMode::Expand`] with the default
+/// [`Substitutor`] settings, so no reference providers are registered.
+/// Unknown placeholders are kept verbatim.
+///
+/// [`SubstitutionMode::Expand`]: crate::SubstitutionMode::Expand
+pub fn expand<L: VarLookup + ?Sized>(template: &str, lookup: &L) -> String {
+    Substitutor::new().expand(template, lookup)
+}
+
+/// Source of variable values for [`expand`] and [`Substitutor`].
+pub trait VarLookup {
+    fn lookup(&self, name: &str) -> Option<String>;
+}
+
+impl<F> VarLookup for F
+where
+    F: Fn(&str) -> Option<String>,
+{
+    fn lookup(&self, name: &str) -> Option<String> {
+        self(name)
+    }
+}
+
+impl VarLookup for BTreeMap<String, String> {
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+impl VarLookup for HashMap<String, String> {
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+impl VarLookup for LoadedEnv {
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.env.lookup(name)
+    }
+}
+
+impl VarLookup for TargetEnv {
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.get_var(name)
+    }
+}
+
+/// Configurable expander for arbitrary strings such as config templates or
+/// command-line arguments.
+///
+/// A `Substitutor` accepts the same placeholder grammar as the loader. Names
+/// are looked up in the supplied [`VarLookup`] first; namespaced references
+/// fall back to the registered [`ReferenceProvider`]s.
+#[derive(Debug, Clone)]
+pub struct Substitutor {
+    key_parsing_mode: KeyParsingMode,
+    references: ReferenceRegistry,
+    filters: bool,
+    dollar_escape: bool,
+}
+
+impl Default for Substitutor {
+    fn default() -> Self {
+        Self {
+            key_parsing_mode: KeyParsingMode::default(),
+            references: ReferenceRegistry::empty(),
+            filters: false,
+            dollar_escape: false,
+        }
+    }
+}
+
+impl Substitutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn key_parsing_mode(mut self, key_parsing_mode: KeyParsingMode) -> Self {
+        self.key_parsing_mode = key_parsing_mode;
+        self
+    }
+
+    /// Enable the built-in `${file:...}`, `${env:...}`, and `${base64:...}`
+    /// reference providers.
+    ///
+    /// Off by default, as for
+    /// [`EnvLoader::builtin_references`](crate::EnvLoader::builtin_references).
+    pub fn builtin_references(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.references.register_builtins();
+        } else {
+            self.references.unregister_builtins();
+        }
+        self
+    }
+
+    /// Register a provider for `${prefix:reference}` placeholders.
+    pub fn reference_provider(
+        mut self,
+        prefix: impl Into<String>,
+        provider: impl ReferenceProvider + 'static,
+    ) -> Self {
+        self.references.register(prefix, provider);
+        self
+    }
+
+    pub fn reference_registry(mut self, references: ReferenceRegistry) -> Self {
+        self.references = references;
+        self
+    }
+
+    /// Enable `${NAME|filter|...}` pipelines.
+    pub fn filters(mut self, filters: bool) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Treat `$$` as a literal `$`.
+    pub fn dollar_escape(mut self, dollar_escape: bool) -> Self {
+        self.dollar_escape = dollar_escape;
+        self
+    }
+
+    pub fn expand<L: VarLookup + ?Sized>(&self, template: &str, lookup: &L) -> String {
+        let options = ExpandOptions {
+            key_parsing_mode: self.key_parsing_mode,
+            references: &self.references,
+            filters: self.filters,
+            dollar_escape: self.dollar_escape,
+        };
+        let permissive = self.key_parsing_mode == KeyParsingMode::Permissive;
+        expand_template(template, options, |placeholder| {
+            match placeholder.namespace {
+                // Only permissive keys can be spelled like a reference; the
+                // provider's answer is final otherwise.
+                Some((prefix, reference)) => permissive
+                    .then(|| lookup.lookup(placeholder.name))
+                    .flatten()
+                    .or_else(|| self.references.resolve(prefix, reference)),
+                None => lookup.lookup(placeholder.name),
+            }
+        })
+    }
+}
+
+/// Resolver for namespaced placeholders such as `${file:/run/secrets/token}`.
+///
+/// Providers return `None` when a reference cannot be resolved; the
+/// placeholder then falls back to its `:-` default or stays verbatim.
+pub trait ReferenceProvider: Send + Sync {
+    fn resolve(&self, reference: &str) -> Option<String>;
+}
+
+impl<F> ReferenceProvider for F
+where
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    fn resolve(&self, reference: &str) -> Option<String> {
+        self(reference)
+    }
+}
+
+/// Prefix-to-provider table used when expanding `${prefix:reference}`.
+///
+/// [`ReferenceRegistry::new`] registers the built-in providers:
+///
+/// - `file`: read a file and trim surrounding whitespace
+/// - `env`: read the process environment, bypassing dotenv file values
+/// - `base64`: decode standard (padded or unpadded) base64 into UTF-8 text
+#[derive(Clone)]
+pub struct ReferenceRegistry {
+    providers: BTreeMap<String, Arc<dyn ReferenceProvider>>,
+}
+
+impl ReferenceRegistry {
+    /// Create a registry with the built-in `file`, `env`, and `base64` providers.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register_builtins();
+        registry
+    }
+
+    pub(crate) fn register_builtins(&mut self) {
+        for (prefix, provider) in BUILTIN_PROVIDERS {
+            self.register(prefix, provider);
+        }
+    }
+
+    pub(crate) fn unregister_builtins(&mut self) {
+        for (prefix, _) in BUILTIN_PROVIDERS {
+            self.unregister(prefix);
+        }
+    }
+
+    /// Create a registry without any providers.
+    pub fn empty() -> Self {
+        Self {
+            providers: BTreeMap::new(),
+        }
+    }
+
+    /// Register a provider for `prefix`, replacing any previous provider.
+    pub fn register(
+        &mut self,
+        prefix: impl Into<String>,
+        provider: impl ReferenceProvider + 'static,
+    ) {
+        self.providers.insert(prefix.into(), Arc::new(provider));
+    }
+
+    /// Builder-style variant of [`ReferenceRegistry::register`].
+    pub fn with_provider(
+        mut self,
+        prefix: impl Into<String>,
+        provider: impl ReferenceProvider + 'static,
+    ) -> Self {
+        self.register(prefix, provider);
+        self
+    }
+
+    /// Remove the provider registered for `prefix`.
+    pub fn unregister(&mut self, prefix: &str) {
+        self.providers.remove(prefix);
+    }
+
+    pub fn contains(&self, prefix: &str) -> bool {
+        self.providers.contains_key(prefix)
+    }
+
+    pub(crate) fn resolve(&self, prefix: &str, reference: &str) -> Option<String> {
+        self.providers
+            .get(prefix)
+            .and_then(|provider| provider.resolve(reference))
+    }
+}
+
+impl Default for ReferenceRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ReferenceRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.providers.keys()).finish()
+    }
+}
+
+fn resolve_file_reference(reference: &str) -> Option<String> {
+    std::fs::read_to_string(Path::new(reference))
+        .ok()
+        .map(|content| content.trim().to_owned())
+}
+
+fn resolve_env_reference(reference: &str) -> Option<String> {
+    std::env::var_os(reference).map(|value| value.to_string_lossy().into_owned())
+}
+
+fn resolve_base64_reference(reference: &str) -> Option<String> {
+    let decoded = decode_base64(reference.trim())?;
+    String::from_utf8(decoded).ok()
+}
+
+pub(crate) struct SubstitutionResolver<'a> {
+    raw_values: HashMap<String, String>,
+    resolved_values: HashMap<String, String>,
+    target: &'a TargetEnv,
+    override_existing: bool,
+    options: ExpandOptions<'a>,
+}
+
+impl<'a> SubstitutionResolver<'a> {
+    pub(crate) fn new(
+        entries: &[Entry],
+        target: &'a TargetEnv,
+        override_existing: bool,
+        options: ExpandOptions<'a>,
+    ) -> Self {
+        let raw_values = entries
+            .iter()
+            .map(|entry| (entry.key.clone(), entry.value.clone()))
+            .collect();
+
+        Self {
+            raw_values,
+            resolved_values: HashMap::new(),
+            target,
+            override_existing,
+            options,
+        }
+    }
+
+    pub(crate) fn resolve_entry(&mut self, key: &str) -> String {
+        self.resolve_key(key, &mut Vec::new())
+    }
+
+    fn resolve_key(&mut self, key: &str, stack: &mut Vec<String>) -> String {
+        if let Some(existing) = self.resolved_values.get(key) {
+            return existing.clone();
+        }
+
+        if !self.override_existing && self.target.contains_key(key) {
+            let existing = self.target.get_var(key).unwrap_or_default();
+            self.resolved_values
+                .insert(key.to_owned(), existing.clone());
+            return existing;
+        }
+
+        let Some(raw_value) = self.raw_values.get(key).cloned() else {
+            return self.target.get_var(key).unwrap_or_default();
+        };
+
+        stack.push(key.to_owned());
+        let expanded = expand_template(&raw_value, self.options, |placeholder| {
+            self.resolve_placeholder(placeholder, stack)
+        });
+        stack.pop();
+
+        self.resolved_values
+            .insert(key.to_owned(), expanded.clone());
+        expanded
+    }
+
+    fn resolve_placeholder(
+        &mut self,
+        placeholder: &PlaceholderRef<'_>,
+        stack: &mut Vec<String>,
+    ) -> Option<String> {
+        let PlaceholderRef { name, namespace } = *placeholder;
+
+        // In permissive mode a loaded or existing key spelled exactly like the
+        // reference wins, so keys such as `env:HOME` keep resolving as plain
+        // keys. Otherwise the provider's answer is final.
+        if let Some((prefix, reference)) = namespace
+            && (self.options.key_parsing_mode == KeyParsingMode::Strict
+                || (!self.raw_values.contains_key(name) && !self.target.contains_key(name)))
+        {
+            return self.options.references.resolve(prefix, reference);
+        }
+
+        if stack.iter().any(|item| item == name) {
+            return None;
+        }
+
+        if self.raw_values.contains_key(name) {
+            Some(self.resolve_key(name, stack))
+        } else {
+            self.target.get_var(name)
+        }
+    }
+}
+
+/// A placeholder located by [`expand_template`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PlaceholderRef<'a> {
+    /// Full name inside the braces, including any namespace prefix.
+    pub(crate) name: &'a str,
+    /// `(prefix, reference)` when the name starts with a registered prefix.
+    pub(crate) namespace: Option<(&'a str, &'a str)>,
+}
+
+/// Grammar switches shared by every [`expand_template`] call of one load.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExpandOptions<'a> {
+    pub(crate) key_parsing_mode: KeyParsingMode,
+    pub(crate) references: &'a ReferenceRegistry,
+    pub(crate) filters: bool,
+    pub(crate) dollar_escape: bool,
+}
+
+/// Expand placeholders in `input`.
+///
+/// `resolve` returns `None` for unknown names; `:-` defaults and filters are
+/// applied afterwards, and a placeholder that still has no value is kept
+/// verbatim.
+pub(crate) fn expand_template<F>(input: &str, options: ExpandOptions<'_>, mut resolve: F) -> String
+where
+    F: FnMut(&PlaceholderRef<'_>) -> Option<String>,
+{
+    let mut out = String::with_capacity(input.len());
+    let mut cursor = 0usize;
+    let mut idx = 0usize;
+    let bytes = input.as_bytes();
+
+    while idx < bytes.len() {
+        if bytes[idx] != b'$' {
+            idx += 1;
+            continue;
+        }
+
+        if idx > 0 && bytes[idx - 1] == b'\\' {
+            out.push_str(&input[cursor..idx - 1]);
+            out.push('$');
+            cursor = idx + 1;
+            idx += 1;
+            continue;
+        }
+
+        if options.dollar_escape && bytes.get(idx + 1) == Some(&b'$') {
+            out.push_str(&input[cursor..=idx]);
+            cursor = idx + 2;
+            idx += 2;
+            continue;
+        }
+
+        let Some(placeholder) = parse_placeholder(input, idx, options) else {
+            idx += 1;
+            continue;
+        };
+
+        let token = &input[idx..placeholder.token_end];
+
+        let mut value = resolve(&placeholder.reference(input, placeholder.name_end));
+        let mut default = placeholder.default;
+        // Permissive keys may contain `:-`, so the whole name is tried first and
+        // the operator only applies when no such key or reference exists.
+        if value.is_none()
+            && let Some(operator_idx) = placeholder.permissive_operator
+        {
+            value = resolve(&placeholder.reference(input, operator_idx));
+            default = Some((operator_idx + 2, placeholder.name_end));
+        }
+        if let Some((start, end)) = default
+            && value.as_deref().is_none_or(str::is_empty)
+        {
+            value = Some(input[start..end].to_owned());
+        }
+        if let Some((start, end)) = placeholder.filters {
+            value = apply_filters(&input[start..end], value);
+        }
+
+        out.push_str(&input[cursor..idx]);
+        out.push_str(value.as_deref().unwrap_or(token));
+
+        cursor = placeholder.token_end;
+        idx = placeholder.token_end;
+    }
+
+    out.push_str(&input[cursor..]);
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Placeholder {
+    name_start: usize,
+    name_end: usize,
+    namespace_end: Option<usize>,
+    default: Option<(usize, usize)>,
+    /// Position of a `:-` inside a permissive name, tried after the full name.
+    permissive_operator: Option<usize>,
+    filters: Option<(usize, usize)>,
+    token_end: usize,
+}
+
+impl Placeholder {
+    fn reference<'a>(&self, input: &'a str, name_end: usize) -> PlaceholderRef<'a> {
+        PlaceholderRef {
+            name: &input[self.name_start..name_end],
+            namespace: self
+                .namespace_end
+                .map(|colon| (&input[self.name_start..colon], &input[colon + 1..name_end])),
+        }
+    }
+}
+
+fn parse_placeholder(input: &str, start: usize, options: ExpandOptions<'_>) -> Option<Placeholder> {
+    let bytes = input.as_bytes();
+    if start + 1 >= bytes.len() {
+        return None;
+    }
+
+    if bytes[start + 1] == b'{' {
+        let mut end = start + 2;
+        while end < bytes.len() && bytes[end] != b'}' {
+            end += 1;
+        }
+
+        if end >= bytes.len() {
+            return None;
+        }
+
+        let name_start = start + 2;
+        let token_end = end + 1;
+
+        let mut filters = None;
+        if options.filters
+            && let Some(pipe_idx) = input[name_start..end].find('|')
+        {
+            let filters_start = name_start + pipe_idx + 1;
+            if !input[filters_start..end].split('|').all(is_valid_filter) {
+                return None;
+            }
+            filters = Some((filters_start, end));
+            end = filters_start - 1;
+        }
+
+        let placeholder = parse_braced(input, name_start, end, options)?;
+        return Some(Placeholder {
+            filters,
+            token_end,
+            ..placeholder
+        });
+    }
+
+    let name_start = start + 1;
+    if !is_unbraced_var_start(bytes[name_start]) {
+        return None;
+    }
+
+    let mut name_end = name_start + 1;
+    while name_end < bytes.len() && is_unbraced_var_char(bytes[name_end]) {
+        name_end += 1;
+    }
+
+    Some(Placeholder {
+        name_start,
+        name_end,
+        namespace_end: None,
+        default: None,
+        permissive_operator: None,
+        filters: None,
+        token_end: name_end,
+    })
+}
+
+/// Parse the `NAME`, `NAME:-default`, or `prefix:reference` part of a braced
+/// placeholder spanning `name_start..end`.
+fn parse_braced(
+    input: &str,
+    name_start: usize,
+    end: usize,
+    options: ExpandOptions<'_>,
+) -> Option<Placeholder> {
+    if let Some(placeholder) = parse_namespaced(input, name_start, end, options) {
+        return Some(placeholder);
+    }
+
+    let key_parsing_mode = options.key_parsing_mode;
+    let mut name_end = end;
+    let mut default = None;
+    let mut permissive_operator = None;
+    if let Some(operator_idx) = input[name_start..end].find(":-") {
+        match key_parsing_mode {
+            KeyParsingMode::Strict => {
+                name_end = name_start + operator_idx;
+                default = Some((name_end + 2, end));
+            }
+            KeyParsingMode::Permissive if operator_idx > 0 => {
+                permissive_operator = Some(name_start + operator_idx);
+            }
+            KeyParsingMode::Permissive => {}
+        }
+    }
+
+    let name = &input[name_start..name_end];
+    if name.is_empty()
+        || !name
+            .bytes()
+            .all(|byte| is_braced_var_char(byte, key_parsing_mode))
+    {
+        return None;
+    }
+
+    Some(Placeholder {
+        name_start,
+        name_end,
+        namespace_end: None,
+        default,
+        permissive_operator,
+        filters: None,
+        token_end: end + 1,
+    })
+}
+
+fn parse_namespaced(
+    input: &str,
+    name_start: usize,
+    end: usize,
+    options: ExpandOptions<'_>,
+) -> Option<Placeholder> {
+    let inner = &input[name_start..end];
+    let colon = inner.find(':')?;
+    let rest = &inner[colon + 1..];
+    // `${NAME:-fallback}` is a default operator, never a namespace.
+    if rest.is_empty() || rest.starts_with('-') || !options.references.contains(&inner[..colon]) {
+        return None;
+    }
+
+    let namespace_end = name_start + colon;
+    let mut name_end = end;
+    let mut default = None;
+    let mut permissive_operator = None;
+    if let Some(operator_idx) = rest.find(":-") {
+        let operator_idx = namespace_end + 1 + operator_idx;
+        match options.key_parsing_mode {
+            KeyParsingMode::Strict => {
+                name_end = operator_idx;
+                default = Some((operator_idx + 2, end));
+            }
+            KeyParsingMode::Permissive => permissive_operator = Some(operator_idx),
+        }
+    }
+    if name_end == namespace_end + 1 {
+        return None;
+    }
+
+    Some(Placeholder {
+        name_start,
+        name_end,
+        namespace_end: Some(namespace_end),
+        default,
+        permissive_operator,
+        filters: None,
+        token_end: end + 1,
+    })
+}
+
+fn is_valid_filter(filter: &str) -> bool {
+    matches!(
+        filter.trim(),
+        "upper" | "lower" | "trim" | "urlencode" | "base64" | "json-escape"
+    ) || filter.trim_start().starts_with("default:")
+}
+
+/// Run a `|`-separated filter pipeline; the spec has already been validated.
+fn apply_filters(spec: &str, mut value: Option<String>) -> Option<String> {
+    for filter in spec.split('|') {
+        if let Some(fallback) = filter.trim_start().strip_prefix("default:") {
+            if value.as_deref().is_none_or(str::is_empty) {
+                value = Some(fallback.to_owned());
+            }
+            continue;
+        }
+
+        let Some(current) = value.as_deref() else {
+            continue;
+        };
+        value = Some(match filter.trim() {
+            "upper" => current.to_uppercase(),
+            "lower" => current.to_lowercase(),
+            "trim" => current.trim().to_owned(),
+            "urlencode" => url_encode(current),
+            "base64" => encode_base64(current.as_bytes()),
+            "json-escape" => json_escape(current),
+            _ => unreachable!("filters are validated while parsing"),
+        });
+    }
+    value
+}
+
+fn url_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(char::from(byte));
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+fn json_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            ch if ch.is_control() && (ch as u32) < 0x20 => {
+                out.push_str(&format!("\\u{:04x}", ch as u32));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+fn is_braced_var_char(byte: u8, key_parsing_mode: KeyParsingMode) -> bool {
+    match key_parsing_mode {
+        KeyParsingMode::Strict => {
+            byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'.' || byte == b'-'
+        }
+        KeyParsingMode::Permissive => is_valid_permissive_key_byte(byte),
+    }
+}
+
+fn is_valid_permissive_key_byte(byte: u8) -> bool {
+    byte.is_ascii() && (b'!'..=b'~').contains(&byte) && byte != b'='
+}
+
+fn is_unbraced_var_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || byte == b'_'
+}
+
+fn is_unbraced_var_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn encode_base64(input: &[u8]) -> String {
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let buffer = chunk.iter().enumerate().fold(0u32, |acc, (idx, byte)| {
+            acc | u32::from(*byte) << (16 - 8 * idx)
+        });
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                let sextet = (buffer >> (18 - 6 * idx)) & 0x3f;
+                out.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches('=');
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0u32;
+
+    for byte in input.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    // A single dangling sextet cannot encode a whole byte.
+    if bits >= 6 {
+        return None;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ExpandOptions, ReferenceRegistry, apply_filters, decode_base64, encode_base64,
+        expand_template,
+    };
+    use crate::model::KeyParsingMode;
+
+    fn options(
+        references: &ReferenceRegistry,
+        key_parsing_mode: KeyParsingMode,
+    ) -> ExpandOptions<'_> {
+        ExpandOptions {
+            key_parsing_mode,
+            references,
+            filters: true,
+            dollar_escape: false,
+        }
+    }
+
+    #[test]
+    fn base64_round_trips_padding_variants() {
+        assert_eq!(encode_base64(b"hello"), "aGVsbG8=");
+        assert_eq!(encode_base64(b"hi!"), "aGkh");
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(decode_base64("aGVsbG8=").as_deref(), Some(&b"hello"[..]));
+        assert_eq!(decode_base64("aGVsbG8").as_deref(), Some(&b"hello"[..]));
+        assert_eq!(decode_base64("").as_deref(), Some(&b""[..]));
+        assert_eq!(decode_base64("a"), None);
+        assert_eq!(decode_base64("a*bc"), None);
+    }
+
+    #[test]
+    fn namespaced_placeholders_require_registered_prefix() {
+        let references = ReferenceRegistry::empty().with_provider("vault", |reference: &str| {
+            Some(format!("secret:{reference}"))
+        });
+        let expanded = expand_template(
+            "${vault:db/password};${other:x}",
+            options(&references, KeyParsingMode::Permissive),
+            |placeholder| match placeholder.namespace {
+                Some((prefix, reference)) => references.resolve(prefix, reference),
+                None => Some(format!("key:{}", placeholder.name)),
+            },
+        );
+        assert_eq!(expanded, "secret:db/password;key:other:x");
+    }
+
+    #[test]
+    fn colon_minus_is_not_mistaken_for_a_namespace() {
+        let references = ReferenceRegistry::new();
+        let expanded = expand_template(
+            "${env:-fallback}",
+            options(&references, KeyParsingMode::Strict),
+            |placeholder| {
+                assert_eq!(placeholder.namespace, None);
+                None
+            },
+        );
+        assert_eq!(expanded, "fallback");
+    }
+
+    #[test]
+    fn filters_run_left_to_right() {
+        assert_eq!(
+            apply_filters("trim|upper", Some("  db.local ".to_owned())).as_deref(),
+            Some("DB.LOCAL")
+        );
+        assert_eq!(
+            apply_filters("urlencode", Some("p@ss w/rd~".to_owned())).as_deref(),
+            Some("p%40ss%20w%2Frd~")
+        );
+        assert_eq!(
+            apply_filters("json-escape", Some("a\"b\\\n\u{1}".to_owned())).as_deref(),
+            Some("a\\\"b\\\\\\n\\u0001")
+        );
+        assert_eq!(
+            apply_filters("default:guest|upper", None).as_deref(),
+            Some("GUEST")
+        );
+        assert_eq!(apply_filters("lower", None), None);
+    }
+
+    #[test]
+    fn unknown_filters_leave_placeholder_verbatim() {
+        let references = ReferenceRegistry::empty();
+        let expanded = expand_template(
+            "${A|shout}",
+            options(&references, KeyParsingMode::Strict),
+            |_| Some("value".to_owned()),
+        );
+        assert_eq!(expanded, "${A|shout}");
+    }
+}
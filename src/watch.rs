@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+use crate::error::Error;
+use crate::loader::EnvLoader;
+use crate::model::LoadedEnv;
+
+/// Keys that differ between two loads, each list sorted.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EnvDiff {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl EnvDiff {
+    pub fn between(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> Self {
+        let mut diff = Self::default();
+        for (key, value) in new {
+            match old.get(key) {
+                None => diff.added.push(key.clone()),
+                Some(previous) if previous != value => diff.changed.push(key.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .keys()
+            .filter(|key| !new.contains_key(*key))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A reload triggered by a file change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvChange {
+    pub diff: EnvDiff,
+    /// The full result of the new load.
+    pub env: LoadedEnv,
+}
+
+/// Polling watcher that reloads an [`EnvLoader`] when its files change.
+///
+/// Watches the files the loader resolves on each poll, so files that appear
+/// later (such as a new `.env.local`) are picked up. Files pulled in by
+/// `# @include` and custom sources are not watched. A file counts as changed
+/// when its modification time or size moved and its content hash differs.
+#[derive(Debug)]
+pub struct EnvWatcher {
+    loader: EnvLoader,
+    files: BTreeMap<PathBuf, Option<Fingerprint>>,
+    current: LoadedEnv,
+}
+
+#[derive(Debug, Clone)]
+struct Fingerprint {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+impl EnvWatcher {
+    /// Load once and record the state of the loader's files.
+    ///
+    /// The loader must have an in-memory target, as for [`EnvLoader::load`].
+    pub fn new(mut loader: EnvLoader) -> Result<Self, Error> {
+        let files = fingerprints(&loader, &BTreeMap::new())?;
+        let current = loader.load_snapshot()?;
+        Ok(Self {
+            loader,
+            files,
+            current,
+        })
+    }
+
+    /// The most recent successful load.
+    pub fn current(&self) -> &LoadedEnv {
+        &self.current
+    }
+
+    /// Check the files once and reload if any changed.
+    ///
+    /// Returns `None` when nothing changed or the reload produced the same
+    /// values. A failed reload, such as a parse error in a half-written file,
+    /// keeps the previous values; the next edit triggers another attempt.
+    pub fn poll(&mut self) -> Result<Option<EnvChange>, Error> {
+        let files = fingerprints(&self.loader, &self.files)?;
+        let unchanged = files
+            .iter()
+            .map(|(path, fingerprint)| (path, fingerprint.as_ref().map(|f| f.hash)))
+            .eq(self
+                .files
+                .iter()
+                .map(|(path, fingerprint)| (path, fingerprint.as_ref().map(|f| f.hash))));
+        self.files = files;
+        if unchanged {
+            return Ok(None);
+        }
+
+        let env = self.loader.load_snapshot()?;
+        let diff = EnvDiff::between(&self.current.env, &env.env);
+        self.current = env;
+        if diff.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(EnvChange {
+            diff,
+            env: self.current.clone(),
+        }))
+    }
+
+    /// Poll every `interval` on a background thread, passing each change or
+    /// reload error to `on_change`.
+    pub fn spawn(
+        mut self,
+        interval: Duration,
+        mut on_change: impl FnMut(Result<EnvChange, Error>) + Send + 'static,
+    ) -> WatchHandle {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                if let Some(result) = self.poll().transpose() {
+                    on_change(result);
+                }
+            }
+            self
+        });
+        WatchHandle {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+
+    /// Like [`EnvWatcher::spawn`], delivering results over a channel.
+    pub fn channel(self, interval: Duration) -> (WatchHandle, Receiver<Result<EnvChange, Error>>) {
+        let (sender, receiver) = mpsc::channel();
+        let handle = self.spawn(interval, move |result| {
+            let _ = sender.send(result);
+        });
+        (handle, receiver)
+    }
+}
+
+/// Background thread started by [`EnvWatcher::spawn`]; dropping it stops
+/// the thread.
+#[derive(Debug)]
+pub struct WatchHandle {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<EnvWatcher>>,
+}
+
+impl WatchHandle {
+    /// Stop polling and return the watcher.
+    pub fn stop(mut self) -> EnvWatcher {
+        self.shutdown().expect("watch thread already joined")
+    }
+
+    fn shutdown(&mut self) -> Option<EnvWatcher> {
+        drop(self.stop.take());
+        let thread = self.thread.take()?;
+        match thread.join() {
+            Ok(watcher) => Some(watcher),
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.shutdown();
+        }
+    }
+}
+
+fn fingerprints(
+    loader: &EnvLoader,
+    previous: &BTreeMap<PathBuf, Option<Fingerprint>>,
+) -> Result<BTreeMap<PathBuf, Option<Fingerprint>>, Error> {
+    let mut files = BTreeMap::new();
+    for path in loader.watched_paths()? {
+        let previous = previous.get(&path).and_then(Option::as_ref);
+        let fingerprint = fingerprint(&path, previous)?;
+        files.insert(path, fingerprint);
+    }
+    Ok(files)
+}
+
+/// Hash `path` unless its modification time and size match `previous`.
+fn fingerprint(path: &Path, previous: Option<&Fingerprint>) -> Result<Option<Fingerprint>, Error> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let modified = metadata.modified().ok();
+    if let Some(previous) = previous
+        && modified.is_some()
+        && previous.modified == modified
+        && previous.len == metadata.len()
+    {
+        return Ok(Some(previous.clone()));
+    }
+
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Ok(Some(Fingerprint {
+        modified,
+        len: bytes.len() as u64,
+        hash: hasher.finish(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnvDiff;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn diff_lists_added_changed_and_removed_keys() {
+        let old = BTreeMap::from([
+            ("KEEP".to_string(), "1".to_string()),
+            ("EDIT".to_string(), "old".to_string()),
+            ("GONE".to_string(), "1".to_string()),
+        ]);
+        let new = BTreeMap::from([
+            ("KEEP".to_string(), "1".to_string()),
+            ("EDIT".to_string(), "new".to_string()),
+            ("ADDED".to_string(), "1".to_string()),
+        ]);
+
+        let diff = EnvDiff::between(&old, &new);
+        assert_eq!(diff.added, ["ADDED"]);
+        assert_eq!(diff.changed, ["EDIT"]);
+        assert_eq!(diff.removed, ["GONE"]);
+        assert!(EnvDiff::between(&new, &new).is_empty());
+    }
+}
@@ -3,8 +3,8 @@
 use std::ffi::OsString;
 use std::os::unix::ffi::OsStringExt;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[test]
 fn run_loads_default_dotenv_file() {
@@ -178,6 +178,185 @@ fn run_expand_fails_when_inherited_env_value_is_not_utf8() {
     );
 }
 
+#[test]
+fn run_env_loads_convention_stack() {
+    let dir = make_temp_dir("cli-env");
+    write_file(&dir.join(".env"), "DOTENVOR_CLI_STAGE=base\n");
+    write_file(
+        &dir.join(".env.production"),
+        "DOTENVOR_CLI_STAGE=production\n",
+    );
+
+    let output = run_dotenv(
+        &dir,
+        &[
+            "run",
+            "-e",
+            "production",
+            "--",
+            "printenv",
+            "DOTENVOR_CLI_STAGE",
+        ],
+        None,
+    );
+
+    assert_success(&output);
+    assert_eq!(stdout_trimmed(&output), "production");
+}
+
+#[test]
+fn run_cascade_stops_at_project_root() {
+    let dir = make_temp_dir("cli-cascade");
+    let project = dir.join("project");
+    let app = project.join("app");
+    std::fs::create_dir_all(project.join(".git")).expect("failed to create .git");
+    std::fs::create_dir_all(&app).expect("failed to create app dir");
+    write_file(&dir.join(".env"), "DOTENVOR_CLI_STRAY=outside\n");
+    write_file(
+        &project.join(".env"),
+        "DOTENVOR_CLI_LEVEL=project\nDOTENVOR_CLI_BASE=1\n",
+    );
+    write_file(&app.join(".env"), "DOTENVOR_CLI_LEVEL=app\n");
+
+    let output = run_dotenv(
+        &app,
+        &[
+            "run",
+            "--cascade",
+            "--",
+            "sh",
+            "-c",
+            "echo \"$DOTENVOR_CLI_LEVEL:$DOTENVOR_CLI_BASE:${DOTENVOR_CLI_STRAY:-none}\"",
+        ],
+        None,
+    );
+
+    assert_success(&output);
+    assert_eq!(stdout_trimmed(&output), "app:1:none");
+}
+
+#[test]
+fn run_skips_protected_keys_unless_allowed() {
+    let dir = make_temp_dir("cli-protected");
+    write_file(&dir.join(".env"), "PERL5OPT=from_file\n");
+
+    let blocked = run_dotenv(&dir, &["run", "--", "printenv", "PERL5OPT"], None);
+    assert!(!blocked.status.success());
+    assert_eq!(stdout_trimmed(&blocked), "");
+    assert!(
+        String::from_utf8_lossy(&blocked.stderr).contains("skipping protected key PERL5OPT"),
+        "stderr should explain the skipped key: {:?}",
+        String::from_utf8_lossy(&blocked.stderr)
+    );
+
+    let allowed = run_dotenv(
+        &dir,
+        &[
+            "run",
+            "--allow-key",
+            "PERL5OPT",
+            "--",
+            "printenv",
+            "PERL5OPT",
+        ],
+        None,
+    );
+    assert_success(&allowed);
+    assert_eq!(stdout_trimmed(&allowed), "from_file");
+}
+
+#[test]
+fn run_watch_restarts_command_and_forwards_signals() {
+    let dir = make_temp_dir("cli-watch");
+    let file = dir.join(".env");
+    let log = dir.join("starts.log");
+    write_file(&file, "DOTENVOR_CLI_MODE=one\n");
+
+    let mut supervisor = spawn_watch(
+        &dir,
+        "5",
+        "echo \"$DOTENVOR_CLI_MODE\" >> starts.log; exec sleep 30",
+    );
+
+    wait_for_file(&log, "one\n");
+    write_file(&file, "# edited\nDOTENVOR_CLI_MODE=two\n");
+    wait_for_file(&log, "one\ntwo\n");
+
+    send_signal(&supervisor, "TERM");
+    let status = supervisor.wait().expect("failed to wait for dotenv");
+    assert_eq!(status.code(), Some(143));
+}
+
+#[test]
+fn run_watch_relays_later_signals_and_kills_after_timeout() {
+    let dir = make_temp_dir("cli-watch-stop");
+    write_file(
+        &dir.join(".env"),
+        "DOTENVOR_CLI_MODE=one
+",
+    );
+    let log = dir.join("starts.log");
+
+    let script = "trap '' INT; trap 'exit 7' TERM; echo up >> starts.log; \
+                  while :; do sleep 0.1; done";
+    let mut supervisor = spawn_watch(&dir, "30", script);
+    wait_for_file(&log, "up\n");
+    send_signal(&supervisor, "INT");
+    std::thread::sleep(Duration::from_millis(300));
+    send_signal(&supervisor, "TERM");
+    let status = supervisor.wait().expect("failed to wait for dotenv");
+    assert_eq!(status.code(), Some(7));
+
+    std::fs::remove_file(&log).expect("failed to remove log");
+    let mut supervisor = spawn_watch(
+        &dir,
+        "1",
+        "trap '' INT; echo up >> starts.log; exec sleep 30",
+    );
+    wait_for_file(&log, "up\n");
+    let started = Instant::now();
+    send_signal(&supervisor, "INT");
+    let status = supervisor.wait().expect("failed to wait for dotenv");
+    assert_eq!(status.code(), Some(137));
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn run_watch_exits_with_command_status() {
+    let dir = make_temp_dir("cli-watch-exit");
+    write_file(&dir.join(".env"), "DOTENVOR_CLI_MODE=one\n");
+
+    let output = run_dotenv(&dir, &["run", "-w", "--", "sh", "-c", "exit 3"], None);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn encrypt_and_decrypt_rewrite_values_in_place() {
+    let dir = make_temp_dir("cli-encrypt");
+    let file = dir.join(".env");
+    write_file(&file, "DOTENVOR_CLI_SECRET=hidden\n");
+
+    assert_success(&run_dotenv(&dir, &["encrypt"], None));
+    assert!(dir.join(".env.keys").is_file());
+    assert!(!std::fs::read_to_string(&file).unwrap().contains("hidden"));
+
+    let output = run_dotenv(
+        &dir,
+        &["run", "--", "printenv", "DOTENVOR_CLI_SECRET"],
+        None,
+    );
+    assert_success(&output);
+    assert_eq!(stdout_trimmed(&output), "hidden");
+
+    assert_success(&run_dotenv(&dir, &["decrypt", "-f", ".env"], None));
+    assert!(
+        std::fs::read_to_string(&file)
+            .unwrap()
+            .contains("DOTENVOR_CLI_SECRET='hidden'")
+    );
+}
+
 fn run_dotenv(dir: &Path, args: &[&str], env_pair: Option<(&str, &str)>) -> Output {
     let mut command = Command::new(dotenv_bin());
     command.current_dir(dir).args(args);
@@ -187,6 +366,49 @@ fn run_dotenv(dir: &Path, args: &[&str], env_pair: Option<(&str, &str)>) -> Outp
     command.output().expect("failed to run dotenv binary")
 }
 
+/// Start `dotenv run --watch` for `script` without a terminal on stdin.
+fn spawn_watch(dir: &Path, stop_timeout: &str, script: &str) -> Child {
+    Command::new(dotenv_bin())
+        .current_dir(dir)
+        .args([
+            "run",
+            "--watch",
+            "--stop-timeout",
+            stop_timeout,
+            "--",
+            "sh",
+            "-c",
+            script,
+        ])
+        .stdin(Stdio::null())
+        .spawn()
+        .expect("failed to run dotenv binary")
+}
+
+fn send_signal(child: &Child, signal: &str) {
+    let status = Command::new("kill")
+        .args([&format!("-{signal}"), &child.id().to_string()])
+        .status()
+        .expect("failed to run kill");
+    assert!(status.success());
+}
+
+fn wait_for_file(path: &Path, expected: &str) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+        if content == expected {
+            return;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "timed out waiting for {expected:?} in {}, found {content:?}",
+            path.display()
+        );
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
 fn stdout_trimmed(output: &Output) -> String {
     String::from_utf8_lossy(&output.stdout)
         .trim_end()
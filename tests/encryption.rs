@@ -0,0 +1,121 @@
+#![cfg(feature = "encryption")]
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dotenvor::{
+    EnvLoader, Error, KeyPair, KeyPattern, SubstitutionMode, decrypt_file, encrypt_file,
+    encrypt_value,
+};
+
+#[test]
+fn encrypted_file_loads_with_generated_keys_file() {
+    let dir = make_temp_dir("encrypt-file");
+    let file = dir.join(".env");
+    write_file(
+        &file,
+        "# database\nexport DB_PASSWORD=\"p@ss $word\"\nDB_HOST=localhost\n",
+    );
+
+    let report = encrypt_file(&file, &[KeyPattern::Glob("DB_PASSWORD".into())])
+        .expect("file should encrypt");
+    assert_eq!(report.encrypted, 1);
+    assert_eq!(
+        report.keys_file.as_deref(),
+        Some(dir.join(".env.keys").as_path())
+    );
+
+    let content = std::fs::read_to_string(&file).expect("encrypted file should be readable");
+    assert!(content.starts_with("DOTENV_PUBLIC_KEY=\""));
+    assert!(content.contains("# database\nexport DB_PASSWORD=\"encrypted:"));
+    assert!(content.contains("DB_HOST=localhost\n"));
+    assert!(!content.contains("p@ss"));
+
+    let loaded = EnvLoader::new()
+        .path(&file)
+        .substitution_mode(SubstitutionMode::Expand)
+        .load()
+        .expect("encrypted file should load");
+    assert_eq!(loaded.env["DB_PASSWORD"], "p@ss $word");
+    assert_eq!(loaded.env["DB_HOST"], "localhost");
+    assert!(loaded.is_secret("DB_PASSWORD"));
+
+    assert_eq!(decrypt_file(&file, &[]).expect("file should decrypt"), 1);
+    let content = std::fs::read_to_string(&file).expect("decrypted file should be readable");
+    assert!(content.contains("export DB_PASSWORD='p@ss $word'\n"));
+}
+
+#[test]
+fn missing_or_wrong_private_key_fails_the_load() {
+    let dir = make_temp_dir("encrypt-missing-key");
+    let file = dir.join(".env.staging");
+    let pair = KeyPair::generate().expect("key pair should generate");
+    let encrypted = encrypt_value(&pair.public_key, "API_TOKEN", "t0ken").unwrap();
+    write_file(&file, &format!("API_TOKEN={encrypted}\n"));
+
+    let err = EnvLoader::new()
+        .path(&file)
+        .load()
+        .expect_err("load without a private key should fail");
+    match err {
+        Error::Encryption(err) => {
+            assert_eq!(err.key.as_deref(), Some("API_TOKEN"));
+            assert!(err.message.contains("DOTENV_PRIVATE_KEY_STAGING"));
+        }
+        other => panic!("expected encryption error, got {other:?}"),
+    }
+
+    let other = KeyPair::generate().unwrap();
+    assert!(matches!(
+        EnvLoader::new()
+            .path(&file)
+            .private_key(other.private_key)
+            .load(),
+        Err(Error::Encryption(_))
+    ));
+
+    let loaded = EnvLoader::new()
+        .path(&file)
+        .private_key(pair.private_key)
+        .load()
+        .expect("load with the private key should succeed");
+    assert_eq!(loaded.env["API_TOKEN"], "t0ken");
+}
+
+#[test]
+fn re_keyed_file_decrypts_with_latest_private_key() {
+    let dir = make_temp_dir("encrypt-rekey");
+    let file = dir.join(".env");
+    write_file(&file, "API_TOKEN=first\n");
+    encrypt_file(&file, &[]).expect("file should encrypt");
+    assert_eq!(decrypt_file(&file, &[]).expect("file should decrypt"), 1);
+
+    // Drop the public key so the next run generates a fresh pair.
+    write_file(&file, "API_TOKEN=second\n");
+    let report = encrypt_file(&file, &[]).expect("file should re-encrypt");
+    assert_eq!(report.encrypted, 1);
+    let keys = std::fs::read_to_string(dir.join(".env.keys")).expect("keys file should exist");
+    assert_eq!(keys.matches("DOTENV_PRIVATE_KEY=").count(), 2);
+
+    let loaded = EnvLoader::new()
+        .path(&file)
+        .load()
+        .expect("latest private key should decrypt");
+    assert_eq!(loaded.env["API_TOKEN"], "second");
+    assert!(!dir.join(".env.dotenvor-tmp").exists());
+}
+
+fn make_temp_dir(name: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    path.push(format!("dotenvor-{name}-{}-{nanos}", std::process::id()));
+    std::fs::create_dir_all(&path).expect("failed to create temp dir");
+    path
+}
+
+fn write_file(path: &Path, content: &str) {
+    std::fs::write(path, content).expect("failed to write test file");
+}
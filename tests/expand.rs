@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use dotenvor::{EnvLoader, KeyParsingMode, Substitutor, TargetEnv, expand};
+
+#[test]
+fn expand_uses_loader_grammar_against_loaded_env() {
+    let mut initial = BTreeMap::new();
+    initial.insert("HOST".to_string(), "db.local".to_string());
+    initial.insert("EMPTY".to_string(), String::new());
+    let loaded = EnvLoader::new()
+        .target(TargetEnv::from_memory(initial))
+        .required(false)
+        .load()
+        .expect("load should succeed");
+
+    assert_eq!(
+        expand(
+            "postgres://$HOST:${PORT:-5432}/${EMPTY:-app}?x=\\$HOST&y=${MISSING}",
+            &loaded
+        ),
+        "postgres://db.local:5432/app?x=$HOST&y=${MISSING}"
+    );
+    assert_eq!(
+        expand("${base64:aGk=};${env:PATH}", &loaded),
+        "${base64:aGk=};${env:PATH}",
+        "built-in reference providers should be opt-in"
+    );
+    assert_eq!(
+        Substitutor::new()
+            .builtin_references(true)
+            .expand("${base64:aGk=}", &loaded),
+        "hi"
+    );
+}
+
+#[test]
+fn expand_accepts_target_env_and_closures() {
+    let mut initial = BTreeMap::new();
+    initial.insert("NAME".to_string(), "target".to_string());
+    let target = TargetEnv::from_memory(initial);
+
+    assert_eq!(expand("hello $NAME", &target), "hello target");
+    assert_eq!(
+        expand("hello ${NAME}", &|name: &str| Some(name.to_lowercase())),
+        "hello name"
+    );
+}
+
+#[test]
+fn substitutor_applies_configured_options() {
+    let mut vars = BTreeMap::new();
+    vars.insert("KEY:ONE".to_string(), "One Value".to_string());
+
+    let substitutor = Substitutor::new()
+        .key_parsing_mode(KeyParsingMode::Permissive)
+        .filters(true)
+        .dollar_escape(true)
+        .reference_provider("const", |reference: &str| Some(reference.to_uppercase()));
+
+    assert_eq!(
+        substitutor.expand("$${KEY:ONE}=${KEY:ONE|urlencode};${const:abc}", &vars),
+        "${KEY:ONE}=One%20Value;ABC"
+    );
+}
+
+#[test]
+fn substitutor_references_do_not_fall_back_to_literal_keys() {
+    let mut vars = BTreeMap::new();
+    vars.insert("const:missing".to_string(), "literal".to_string());
+    let substitutor = Substitutor::new().reference_provider("const", |reference: &str| {
+        (reference == "abc").then(|| "ABC".to_string())
+    });
+
+    assert_eq!(
+        substitutor.expand("${const:missing:-default};${const:nope}", &vars),
+        "default;${const:nope}"
+    );
+    assert_eq!(
+        substitutor
+            .key_parsing_mode(KeyParsingMode::Permissive)
+            .expand("${const:missing}", &vars),
+        "literal"
+    );
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "tokio")]
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dotenvor::{EnvLoader, EnvSource, Error, SubstitutionMode};
+
+#[tokio::test]
+async fn load_async_matches_sync_merge_order() {
+    let dir = make_temp_dir("load-async");
+    let base = dir.join(".env");
+    let local = dir.join(".env.local");
+    write_file(&base, "HOST=localhost\nPORT=5432\nURL=${HOST}:${PORT}\n");
+    write_file(&local, "PORT=6543\n");
+
+    let loader = || {
+        EnvLoader::new()
+            .source_str("defaults", "HOST=default\nDEBUG=false\n")
+            .path(&base)
+            .path(dir.join("missing.env"))
+            .path(&local)
+            .required(false)
+            .substitution_mode(SubstitutionMode::Expand)
+    };
+    let loaded = loader().load_async().await.expect("load should succeed");
+    assert_eq!(loaded, loader().load().expect("load should succeed"));
+    assert_eq!(loaded.report.files_read, 3);
+    assert_eq!(loaded.env["URL"], "localhost:6543");
+    assert_eq!(loaded.origins["PORT"].path, local);
+}
+
+#[tokio::test]
+async fn load_async_reports_first_error_in_stack_order() {
+    let dir = make_temp_dir("load-async-errors");
+    let malformed = dir.join("malformed.env");
+    write_file(&malformed, "KEY=\"unterminated\n");
+
+    let err = EnvLoader::new()
+        .path(&malformed)
+        .path(dir.join("missing.env"))
+        .load_async()
+        .await
+        .expect_err("malformed file should fail first");
+    assert!(matches!(err, Error::Parse(_)));
+
+    let err = EnvLoader::new()
+        .path(dir.join("missing.env"))
+        .path(&malformed)
+        .load_async()
+        .await
+        .expect_err("missing file should fail first");
+    assert!(matches!(err, Error::Io(ref io) if io.kind() == std::io::ErrorKind::NotFound));
+}
+
+#[tokio::test]
+async fn load_async_reads_includes_and_custom_sources() {
+    let dir = make_temp_dir("load-async-includes");
+    let file = dir.join(".env");
+    write_file(&dir.join("shared.env"), "SHARED=1\nPORT=5432\n");
+    write_file(&file, "# @include shared.env\nPORT=6543\n");
+
+    let loader = || {
+        EnvLoader::new()
+            .path(&file)
+            .source_reader("stdin", std::io::Cursor::new("FROM_READER=1\n"))
+            .includes(true)
+    };
+    let loaded = loader().load_async().await.expect("load should succeed");
+    assert_eq!(loaded, loader().load().expect("load should succeed"));
+    assert_eq!(loaded.env["SHARED"], "1");
+    assert_eq!(loaded.env["PORT"], "6543");
+    assert_eq!(loaded.env["FROM_READER"], "1");
+}
+
+struct PanickingSource;
+
+impl EnvSource for PanickingSource {
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed("panicking")
+    }
+
+    fn read(&self) -> std::io::Result<Vec<u8>> {
+        panic!("source exploded");
+    }
+}
+
+#[tokio::test]
+#[should_panic(expected = "source exploded")]
+async fn load_async_propagates_source_panics() {
+    let _ = EnvLoader::new().source(PanickingSource).load_async().await;
+}
+
+fn make_temp_dir(name: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    path.push(format!("dotenvor-{name}-{}-{nanos}", std::process::id()));
+    std::fs::create_dir_all(&path).expect("failed to create temp dir");
+    path
+}
+
+fn write_file(path: &Path, content: &str) {
+    std::fs::write(path, content).expect("failed to write test file");
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "serde")]
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dotenvor::{EnvLoader, Origin};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Config {
+    name: String,
+    debug: bool,
+    workers: u16,
+    ratio: f64,
+    hosts: Vec<String>,
+    timeout: Option<u32>,
+    db: Database,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[test]
+fn deserializes_prefixed_nested_config() {
+    let dir = make_temp_dir("serde-config");
+    let file = dir.join(".env");
+    write_file(
+        &file,
+        "APP_NAME=demo\n\
+         APP_DEBUG=yes\n\
+         app_workers=8\n\
+         APP_RATIO=0.5\n\
+         APP_HOSTS=a.local, b.local\n\
+         APP_DB__HOST=db.local\n\
+         APP_DB__PORT=5432\n\
+         OTHER=ignored\n",
+    );
+
+    let config = EnvLoader::new()
+        .path(&file)
+        .load()
+        .expect("load should succeed")
+        .deserialize_prefixed::<Config>("APP_")
+        .expect("deserialize should succeed");
+
+    assert_eq!(
+        config,
+        Config {
+            name: "demo".to_string(),
+            debug: true,
+            workers: 8,
+            ratio: 0.5,
+            hosts: vec!["a.local".to_string(), "b.local".to_string()],
+            timeout: None,
+            db: Database {
+                host: "db.local".to_string(),
+                port: 5432,
+            },
+        }
+    );
+}
+
+#[test]
+fn errors_report_key_and_source_location() {
+    let dir = make_temp_dir("serde-error");
+    let file = dir.join(".env");
+    write_file(&file, "HOST=db.local\n\nPORT=not-a-port\n");
+
+    let err = EnvLoader::new()
+        .path(&file)
+        .load()
+        .expect("load should succeed")
+        .deserialize::<Database>()
+        .expect_err("deserialize should fail");
+
+    assert_eq!(err.key.as_deref(), Some("PORT"));
+    assert_eq!(
+        err.origin,
+        Some(Origin {
+            path: file.clone(),
+            line: 3,
+        })
+    );
+    assert!(
+        err.to_string().starts_with(&format!(
+            "PORT ({}:3): invalid value `not-a-port`",
+            file.display()
+        )),
+        "unexpected message: {err}"
+    );
+}
+
+#[test]
+fn missing_nested_fields_name_the_parent_key() {
+    let dir = make_temp_dir("serde-missing");
+    let file = dir.join(".env");
+    write_file(&file, "DB__HOST=db.local\n");
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Outer {
+        db: Database,
+    }
+
+    let err = EnvLoader::new()
+        .path(&file)
+        .load()
+        .expect("load should succeed")
+        .deserialize::<Outer>()
+        .expect_err("deserialize should fail");
+
+    assert_eq!(err.key.as_deref(), Some("DB"));
+    assert_eq!(err.message, "missing field `port`");
+}
+
+#[test]
+fn errors_redact_secret_values() {
+    let dir = make_temp_dir("serde-secret");
+    let file = dir.join(".env");
+    write_file(&file, "API_TOKEN=not-a-number\n");
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Tokens {
+        api_token: u64,
+    }
+
+    let err = EnvLoader::new()
+        .path(&file)
+        .load()
+        .expect("load should succeed")
+        .deserialize::<Tokens>()
+        .expect_err("deserialize should fail");
+
+    assert!(!err.to_string().contains("not-a-number"), "leaked: {err}");
+    assert!(err.message.starts_with("invalid value `[REDACTED]`"));
+}
+
+fn make_temp_dir(name: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    path.push(format!("dotenvor-{name}-{}-{nanos}", std::process::id()));
+    std::fs::create_dir_all(&path).expect("failed to create temp dir");
+    path
+}
+
+fn write_file(path: &Path, content: &str) {
+    std::fs::write(path, content).expect("failed to write test file");
+}
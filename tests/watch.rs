@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dotenvor::{EnvLoader, EnvWatcher, Error};
+
+#[test]
+fn poll_reports_changed_keys_after_edits() {
+    let dir = make_temp_dir("watch-poll");
+    let base = dir.join(".env");
+    let local = dir.join(".env.local");
+    write_file(&base, "HOST=localhost\nPORT=5432\n");
+
+    let mut watcher = EnvWatcher::new(EnvLoader::new().path(&base).path(&local).required(false))
+        .expect("watcher should start");
+    assert_eq!(watcher.current().env["PORT"], "5432");
+    assert!(watcher.poll().expect("poll should succeed").is_none());
+
+    write_file(&local, "PORT=6543\nDEBUG=1\n");
+    let change = watcher
+        .poll()
+        .expect("poll should succeed")
+        .expect("new file should trigger a reload");
+    assert_eq!(change.diff.added, ["DEBUG"]);
+    assert_eq!(change.diff.changed, ["PORT"]);
+    assert!(change.diff.removed.is_empty());
+    assert_eq!(change.env.env["PORT"], "6543");
+
+    write_file(
+        &local,
+        "# only a comment now, longer than before\nPORT=6543\nDEBUG=1\n",
+    );
+    assert!(watcher.poll().expect("poll should succeed").is_none());
+
+    write_file(&local, "PORT=\"unterminated\n");
+    let err = watcher.poll().expect_err("malformed file should fail");
+    assert!(matches!(err, Error::Parse(_)));
+    assert_eq!(watcher.current().env["PORT"], "6543");
+
+    std::fs::remove_file(&local).expect("failed to remove file");
+    let change = watcher
+        .poll()
+        .expect("poll should succeed")
+        .expect("removed file should trigger a reload");
+    assert_eq!(change.diff.removed, ["DEBUG"]);
+    assert_eq!(change.diff.changed, ["PORT"]);
+}
+
+#[test]
+fn channel_delivers_changes_from_background_thread() {
+    let dir = make_temp_dir("watch-channel");
+    let file = dir.join(".env");
+    write_file(&file, "MODE=one\n");
+
+    let watcher = EnvWatcher::new(EnvLoader::new().path(&file)).expect("watcher should start");
+    let (handle, changes) = watcher.channel(Duration::from_millis(10));
+
+    write_file(&file, "MODE=three\n");
+    let change = changes
+        .recv_timeout(Duration::from_secs(5))
+        .expect("change should be delivered")
+        .expect("reload should succeed");
+    assert_eq!(change.diff.changed, ["MODE"]);
+    assert_eq!(change.env.env["MODE"], "three");
+
+    let watcher = handle.stop();
+    assert_eq!(watcher.current().env["MODE"], "three");
+}
+
+fn make_temp_dir(name: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    path.push(format!("dotenvor-{name}-{}-{nanos}", std::process::id()));
+    std::fs::create_dir_all(&path).expect("failed to create temp dir");
+    path
+}
+
+fn write_file(path: &Path, content: &str) {
+    std::fs::write(path, content).expect("failed to write test file");
+}